@@ -63,6 +63,7 @@ const buildJob = job("build", {
     },
 
     { name: "Test debug", if: isDebug, run: "cargo test" },
+    { name: "Test native binaries", if: isDebug, run: "cargo test --features process,cli" },
     { name: "Test release", if: isRelease, run: "cargo test --release" },
 
     getTagVersion,
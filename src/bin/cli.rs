@@ -0,0 +1,163 @@
+//! Formats PHP read from stdin and writes the result to stdout.
+//!
+//! Exit codes:
+//!
+//! - `0` - the input was already formatted
+//! - `1` - the input changed when formatted
+//! - `2` - the input has a syntax error
+//! - `3` - the arguments or configuration are invalid, or another error occurred
+
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::Result;
+use anyhow::bail;
+use dprint_core::configuration::ConfigKeyMap;
+use dprint_core::configuration::ConfigKeyValue;
+use dprint_core::configuration::GlobalConfiguration;
+use dprint_core::configuration::resolve_global_config;
+use dprint_plugin_mago::configuration::Configuration;
+use dprint_plugin_mago::configuration::resolve_config;
+use dprint_plugin_mago::format_text;
+use mago_syntax::error::ParseError;
+
+const EXIT_UNCHANGED: u8 = 0;
+const EXIT_CHANGED: u8 = 1;
+const EXIT_SYNTAX_ERROR: u8 = 2;
+const EXIT_ERROR: u8 = 3;
+
+const USAGE: &str = "Usage: dprint-plugin-mago-cli [--config <path>] [--file-path <path>] [--check]
+
+Formats PHP read from stdin and writes the result to stdout.
+
+Options:
+  --config <path>     A dprint.json file or a JSON file containing only the \"mago\" configuration
+  --file-path <path>  The path of the file being formatted, used to detect the file extension
+  --check             Only report whether the input would change, without writing it to stdout";
+
+struct Args {
+  config_path: Option<PathBuf>,
+  file_path: PathBuf,
+  check: bool,
+}
+
+fn main() -> ExitCode {
+  match run() {
+    Ok(code) => ExitCode::from(code),
+    Err(err) => {
+      eprintln!("{:#}", err);
+      ExitCode::from(EXIT_ERROR)
+    }
+  }
+}
+
+fn run() -> Result<u8> {
+  let Some(args) = parse_args(std::env::args().skip(1))? else {
+    println!("{}", USAGE);
+    return Ok(EXIT_UNCHANGED);
+  };
+  let mut input_text = String::new();
+  std::io::stdin().read_to_string(&mut input_text)?;
+
+  let config = match &args.config_path {
+    Some(config_path) => read_config(config_path)?,
+    None => resolve_and_check_config(ConfigKeyMap::new(), &GlobalConfiguration::default())?,
+  };
+
+  let maybe_text = match format_text(&args.file_path, &input_text, &config) {
+    Ok(maybe_text) => maybe_text,
+    Err(err) if err.downcast_ref::<ParseError>().is_some() => {
+      eprintln!("{}", err);
+      return Ok(EXIT_SYNTAX_ERROR);
+    }
+    Err(err) => return Err(err),
+  };
+
+  if !args.check {
+    let output_text = maybe_text.as_deref().unwrap_or(&input_text);
+    std::io::stdout().write_all(output_text.as_bytes())?;
+  }
+
+  Ok(if maybe_text.is_some() {
+    EXIT_CHANGED
+  } else {
+    EXIT_UNCHANGED
+  })
+}
+
+/// Parses the command line arguments, returning `None` when help was requested.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>> {
+  let mut config_path = None;
+  let mut file_path = None;
+  let mut check = false;
+
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--config" => config_path = Some(PathBuf::from(expect_value(&mut args, &arg)?)),
+      "--file-path" => file_path = Some(PathBuf::from(expect_value(&mut args, &arg)?)),
+      "--check" => check = true,
+      "-h" | "--help" => return Ok(None),
+      _ => bail!("Unknown argument: {}\n\n{}", arg, USAGE),
+    }
+  }
+
+  Ok(Some(Args {
+    config_path,
+    // assume PHP when no path is provided
+    file_path: file_path.unwrap_or_else(|| PathBuf::from("file.php")),
+    check,
+  }))
+}
+
+fn expect_value(args: &mut impl Iterator<Item = String>, arg: &str) -> Result<String> {
+  match args.next() {
+    Some(value) => Ok(value),
+    None => bail!("Missing value for {}.", arg),
+  }
+}
+
+fn read_config(config_path: &Path) -> Result<Configuration> {
+  let config_text = std::fs::read_to_string(config_path)
+    .map_err(|err| anyhow::anyhow!("Failed reading {}: {:#}", config_path.display(), err))?;
+  // dprint.json files may have comments and trailing commas
+  let mut config_map: ConfigKeyMap = jsonc_parser::parse_to_serde_value(&config_text, &Default::default())
+    .map_err(|err| anyhow::anyhow!("Failed parsing {}: {:#}", config_path.display(), err))?;
+
+  // a dprint.json has the plugin's configuration in a "mago" section and
+  // may provide global configuration to fall back to
+  if config_map.contains_key("mago") || config_map.contains_key("plugins") {
+    let plugin_config = match config_map.shift_remove("mago") {
+      Some(ConfigKeyValue::Object(plugin_config)) => plugin_config,
+      Some(_) => bail!(
+        "Expected the \"mago\" property in {} to be an object.",
+        config_path.display()
+      ),
+      None => ConfigKeyMap::new(),
+    };
+    let global_config_result = resolve_global_config(&mut config_map);
+    print_diagnostics(&global_config_result.diagnostics)?;
+    resolve_and_check_config(plugin_config, &global_config_result.config)
+  } else {
+    resolve_and_check_config(config_map, &GlobalConfiguration::default())
+  }
+}
+
+fn resolve_and_check_config(config_map: ConfigKeyMap, global_config: &GlobalConfiguration) -> Result<Configuration> {
+  let config_result = resolve_config(config_map, global_config);
+  print_diagnostics(&config_result.diagnostics)?;
+  Ok(config_result.config)
+}
+
+fn print_diagnostics(diagnostics: &[dprint_core::configuration::ConfigurationDiagnostic]) -> Result<()> {
+  if diagnostics.is_empty() {
+    return Ok(());
+  }
+
+  for diagnostic in diagnostics {
+    eprintln!("{}: {}", diagnostic.property_name, diagnostic.message);
+  }
+  bail!("Invalid configuration.")
+}
@@ -114,6 +114,165 @@ generate_str_to_from![
   [LengthDescending, "length-descending"]
 ];
 
+#[derive(Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClassElementGroup {
+  UseTrait,
+  Case,
+  Constant,
+  ConstantPublic,
+  ConstantProtected,
+  ConstantPrivate,
+  Property,
+  PropertyStatic,
+  PropertyReadonly,
+  PropertyPublic,
+  PropertyProtected,
+  PropertyPrivate,
+  PropertyPublicStatic,
+  PropertyProtectedStatic,
+  PropertyPrivateStatic,
+  PropertyPublicReadonly,
+  PropertyProtectedReadonly,
+  PropertyPrivateReadonly,
+  Construct,
+  Destruct,
+  Magic,
+  Method,
+  MethodStatic,
+  MethodAbstract,
+  MethodPublic,
+  MethodProtected,
+  MethodPrivate,
+  MethodPublicStatic,
+  MethodProtectedStatic,
+  MethodPrivateStatic,
+  MethodPublicAbstract,
+  MethodProtectedAbstract,
+  MethodPrivateAbstract,
+}
+
+generate_str_to_from![
+  ClassElementGroup,
+  [UseTrait, "use-trait"],
+  [Case, "case"],
+  [Constant, "constant"],
+  [ConstantPublic, "constant-public"],
+  [ConstantProtected, "constant-protected"],
+  [ConstantPrivate, "constant-private"],
+  [Property, "property"],
+  [PropertyStatic, "property-static"],
+  [PropertyReadonly, "property-readonly"],
+  [PropertyPublic, "property-public"],
+  [PropertyProtected, "property-protected"],
+  [PropertyPrivate, "property-private"],
+  [PropertyPublicStatic, "property-public-static"],
+  [PropertyProtectedStatic, "property-protected-static"],
+  [PropertyPrivateStatic, "property-private-static"],
+  [PropertyPublicReadonly, "property-public-readonly"],
+  [PropertyProtectedReadonly, "property-protected-readonly"],
+  [PropertyPrivateReadonly, "property-private-readonly"],
+  [Construct, "construct"],
+  [Destruct, "destruct"],
+  [Magic, "magic"],
+  [Method, "method"],
+  [MethodStatic, "method-static"],
+  [MethodAbstract, "method-abstract"],
+  [MethodPublic, "method-public"],
+  [MethodProtected, "method-protected"],
+  [MethodPrivate, "method-private"],
+  [MethodPublicStatic, "method-public-static"],
+  [MethodProtectedStatic, "method-protected-static"],
+  [MethodPrivateStatic, "method-private-static"],
+  [MethodPublicAbstract, "method-public-abstract"],
+  [MethodProtectedAbstract, "method-protected-abstract"],
+  [MethodPrivateAbstract, "method-private-abstract"]
+];
+
+#[derive(Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArraySyntax {
+  Short,
+  Long,
+  Preserve,
+}
+
+generate_str_to_from![ArraySyntax, [Short, "short"], [Long, "long"], [Preserve, "preserve"]];
+
+#[derive(Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EchoTagStyle {
+  Short,
+  Long,
+  Preserve,
+}
+
+generate_str_to_from![EchoTagStyle, [Short, "short"], [Long, "long"], [Preserve, "preserve"]];
+
+#[derive(Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LetterCase {
+  Lower,
+  Upper,
+  Preserve,
+}
+
+generate_str_to_from![LetterCase, [Lower, "lower"], [Upper, "upper"], [Preserve, "preserve"]];
+
+#[derive(Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HeredocStyle {
+  Preserve,
+  PreferNowdoc,
+}
+
+generate_str_to_from![HeredocStyle, [Preserve, "preserve"], [PreferNowdoc, "prefer-nowdoc"]];
+
+#[derive(Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ElseIfStyle {
+  Elseif,
+  ElseIf,
+  Preserve,
+}
+
+generate_str_to_from![
+  ElseIfStyle,
+  [Elseif, "elseif"],
+  [ElseIf, "else-if"],
+  [Preserve, "preserve"]
+];
+
+#[derive(Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AlternativeSyntax {
+  Preserve,
+  Braces,
+  AlternativeInTemplates,
+}
+
+generate_str_to_from![
+  AlternativeSyntax,
+  [Preserve, "preserve"],
+  [Braces, "braces"],
+  [AlternativeInTemplates, "alternative-in-templates"]
+];
+
+#[derive(Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileHeaderMode {
+  Insert,
+  Replace,
+  Remove,
+}
+
+generate_str_to_from![
+  FileHeaderMode,
+  [Insert, "insert"],
+  [Replace, "replace"],
+  [Remove, "remove"]
+];
+
 #[derive(Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Configuration {
@@ -135,6 +294,7 @@ pub struct Configuration {
   // Brace styles
   pub control_brace_style: Option<BraceStyle>,
   pub following_clause_on_newline: Option<bool>,
+  pub require_control_braces: Option<bool>,
   pub closure_brace_style: Option<BraceStyle>,
   pub function_brace_style: Option<BraceStyle>,
   pub method_brace_style: Option<BraceStyle>,
@@ -184,8 +344,18 @@ pub struct Configuration {
   // Use statement organization
   pub sort_uses: Option<SortUses>,
   pub sort_class_methods: Option<bool>,
+  pub ordered_class_elements: Option<Vec<ClassElementGroup>>,
+  pub ordered_class_elements_sort_order: Option<SortOrder>,
+  pub sort_implements: Option<SortOrder>,
+  pub sort_trait_uses: Option<SortOrder>,
+  pub sort_enum_cases: Option<SortOrder>,
   pub separate_use_types: Option<bool>,
   pub expand_use_groups: Option<bool>,
+  pub remove_unused_uses: Option<bool>,
+  pub import_fully_qualified_names: Option<bool>,
+  pub import_fully_qualified_classes: Option<bool>,
+  pub import_fully_qualified_functions: Option<bool>,
+  pub import_fully_qualified_constants: Option<bool>,
 
   // Type hints and syntax
   pub null_type_hint: Option<NullTypeHint>,
@@ -193,6 +363,39 @@ pub struct Configuration {
   pub parentheses_in_new_expression: Option<bool>,
   pub parentheses_in_exit_and_die: Option<bool>,
   pub parentheses_in_attribute: Option<bool>,
+  pub require_strict_types: Option<bool>,
+  pub array_syntax: Option<ArraySyntax>,
+  pub list_syntax: Option<ArraySyntax>,
+  pub replace_short_open_tags: Option<bool>,
+  pub echo_tag_style: Option<EchoTagStyle>,
+  pub normalize_casts: Option<bool>,
+  pub heredoc_style: Option<HeredocStyle>,
+  pub else_if_style: Option<ElseIfStyle>,
+  pub alternative_syntax: Option<AlternativeSyntax>,
+  pub explicit_visibility: Option<bool>,
+  pub explicit_visibility_properties: Option<bool>,
+  pub explicit_visibility_methods: Option<bool>,
+  pub explicit_visibility_constants: Option<bool>,
+
+  // Numeric literals
+  pub numeric_literal_hex_digit_case: Option<LetterCase>,
+  pub numeric_literal_prefix_case: Option<LetterCase>,
+  pub numeric_literal_exponent_case: Option<LetterCase>,
+  pub numeric_literal_separator_threshold: Option<u8>,
+
+  // File header
+  pub file_header: Option<String>,
+  pub file_header_mode: Option<FileHeaderMode>,
+  pub file_header_year: Option<u16>,
+
+  // Docblock formatting
+  pub format_docblocks: Option<bool>,
+  pub docblock_tag_order: Option<Vec<String>>,
+
+  // Comments
+  pub space_after_line_comment: Option<bool>,
+  pub convert_single_line_block_comments: Option<bool>,
+  pub wrap_comments: Option<bool>,
 
   // Space control settings
   pub space_before_arrow_function_parameter_list_parenthesis: Option<bool>,
@@ -237,4 +440,7 @@ pub struct Configuration {
   pub separate_trait_use: Option<bool>,
   pub indent_heredoc: Option<bool>,
   pub uppercase_literal_keyword: Option<bool>,
+  pub lowercase_keywords: Option<bool>,
+  pub lowercase_native_types: Option<bool>,
+  pub uppercase_magic_constants: Option<bool>,
 }
@@ -66,6 +66,7 @@ pub fn resolve_config(
     // Brace styles
     control_brace_style: get_nullable_value(&mut config, "controlBraceStyle", &mut diagnostics),
     following_clause_on_newline: get_nullable_value(&mut config, "followingClauseOnNewline", &mut diagnostics),
+    require_control_braces: get_nullable_value(&mut config, "requireControlBraces", &mut diagnostics),
     closure_brace_style: get_nullable_value(&mut config, "closureBraceStyle", &mut diagnostics),
     function_brace_style: get_nullable_value(&mut config, "functionBraceStyle", &mut diagnostics),
     method_brace_style: get_nullable_value(&mut config, "methodBraceStyle", &mut diagnostics),
@@ -179,8 +180,35 @@ pub fn resolve_config(
     // Use statement organization
     sort_uses: get_nullable_value(&mut config, "sortUses", &mut diagnostics),
     sort_class_methods: get_nullable_value(&mut config, "sortClassMethods", &mut diagnostics),
+    ordered_class_elements: get_nullable_vec(
+      &mut config,
+      "orderedClassElements",
+      |value, index, diagnostics| get_array_value(value, "orderedClassElements", index, diagnostics),
+      &mut diagnostics,
+    ),
+    ordered_class_elements_sort_order: get_nullable_value(
+      &mut config,
+      "orderedClassElementsSortOrder",
+      &mut diagnostics,
+    ),
+    sort_implements: get_nullable_value(&mut config, "sortImplements", &mut diagnostics),
+    sort_trait_uses: get_nullable_value(&mut config, "sortTraitUses", &mut diagnostics),
+    sort_enum_cases: get_nullable_value(&mut config, "sortEnumCases", &mut diagnostics),
     separate_use_types: get_nullable_value(&mut config, "separateUseTypes", &mut diagnostics),
     expand_use_groups: get_nullable_value(&mut config, "expandUseGroups", &mut diagnostics),
+    remove_unused_uses: get_nullable_value(&mut config, "removeUnusedUses", &mut diagnostics),
+    import_fully_qualified_names: get_nullable_value(&mut config, "importFullyQualifiedNames", &mut diagnostics),
+    import_fully_qualified_classes: get_nullable_value(&mut config, "importFullyQualifiedClasses", &mut diagnostics),
+    import_fully_qualified_functions: get_nullable_value(
+      &mut config,
+      "importFullyQualifiedFunctions",
+      &mut diagnostics,
+    ),
+    import_fully_qualified_constants: get_nullable_value(
+      &mut config,
+      "importFullyQualifiedConstants",
+      &mut diagnostics,
+    ),
 
     // Type hints and syntax
     null_type_hint: get_nullable_value(&mut config, "nullTypeHint", &mut diagnostics),
@@ -192,6 +220,52 @@ pub fn resolve_config(
     parentheses_in_new_expression: get_nullable_value(&mut config, "parenthesesInNewExpression", &mut diagnostics),
     parentheses_in_exit_and_die: get_nullable_value(&mut config, "parenthesesInExitAndDie", &mut diagnostics),
     parentheses_in_attribute: get_nullable_value(&mut config, "parenthesesInAttribute", &mut diagnostics),
+    require_strict_types: get_nullable_value(&mut config, "requireStrictTypes", &mut diagnostics),
+    array_syntax: get_nullable_value(&mut config, "arraySyntax", &mut diagnostics),
+    list_syntax: get_nullable_value(&mut config, "listSyntax", &mut diagnostics),
+    replace_short_open_tags: get_nullable_value(&mut config, "replaceShortOpenTags", &mut diagnostics),
+    echo_tag_style: get_nullable_value(&mut config, "echoTagStyle", &mut diagnostics),
+    normalize_casts: get_nullable_value(&mut config, "normalizeCasts", &mut diagnostics),
+    heredoc_style: get_nullable_value(&mut config, "heredocStyle", &mut diagnostics),
+    else_if_style: get_nullable_value(&mut config, "elseIfStyle", &mut diagnostics),
+    alternative_syntax: get_nullable_value(&mut config, "alternativeSyntax", &mut diagnostics),
+    explicit_visibility: get_nullable_value(&mut config, "explicitVisibility", &mut diagnostics),
+    explicit_visibility_properties: get_nullable_value(&mut config, "explicitVisibilityProperties", &mut diagnostics),
+    explicit_visibility_methods: get_nullable_value(&mut config, "explicitVisibilityMethods", &mut diagnostics),
+    explicit_visibility_constants: get_nullable_value(&mut config, "explicitVisibilityConstants", &mut diagnostics),
+
+    // Numeric literals
+    numeric_literal_hex_digit_case: get_nullable_value(&mut config, "numericLiteralHexDigitCase", &mut diagnostics),
+    numeric_literal_prefix_case: get_nullable_value(&mut config, "numericLiteralPrefixCase", &mut diagnostics),
+    numeric_literal_exponent_case: get_nullable_value(&mut config, "numericLiteralExponentCase", &mut diagnostics),
+    numeric_literal_separator_threshold: get_nullable_value(
+      &mut config,
+      "numericLiteralSeparatorThreshold",
+      &mut diagnostics,
+    ),
+
+    // File header
+    file_header: get_nullable_value(&mut config, "fileHeader", &mut diagnostics),
+    file_header_mode: get_nullable_value(&mut config, "fileHeaderMode", &mut diagnostics),
+    file_header_year: get_nullable_value(&mut config, "fileHeaderYear", &mut diagnostics),
+
+    // Docblock formatting
+    format_docblocks: get_nullable_value(&mut config, "formatDocblocks", &mut diagnostics),
+    docblock_tag_order: get_nullable_vec(
+      &mut config,
+      "docblockTagOrder",
+      |value, index, diagnostics| get_array_value(value, "docblockTagOrder", index, diagnostics),
+      &mut diagnostics,
+    ),
+
+    // Comments
+    space_after_line_comment: get_nullable_value(&mut config, "spaceAfterLineComment", &mut diagnostics),
+    convert_single_line_block_comments: get_nullable_value(
+      &mut config,
+      "convertSingleLineBlockComments",
+      &mut diagnostics,
+    ),
+    wrap_comments: get_nullable_value(&mut config, "wrapComments", &mut diagnostics),
 
     // Space control settings
     space_before_arrow_function_parameter_list_parenthesis: get_nullable_value(
@@ -312,8 +386,39 @@ pub fn resolve_config(
     separate_trait_use: get_nullable_value(&mut config, "separateTraitUse", &mut diagnostics),
     indent_heredoc: get_nullable_value(&mut config, "indentHeredoc", &mut diagnostics),
     uppercase_literal_keyword: get_nullable_value(&mut config, "uppercaseLiteralKeyword", &mut diagnostics),
+    lowercase_keywords: get_nullable_value(&mut config, "lowercaseKeywords", &mut diagnostics),
+    lowercase_native_types: get_nullable_value(&mut config, "lowercaseNativeTypes", &mut diagnostics),
+    uppercase_magic_constants: get_nullable_value(&mut config, "uppercaseMagicConstants", &mut diagnostics),
   };
 
+  // the Wasm plugin has no clock to get the current year from
+  if cfg!(target_arch = "wasm32")
+    && resolved_config.file_header_year.is_none()
+    && resolved_config
+      .file_header
+      .as_deref()
+      .is_some_and(|template| template.contains("{year}"))
+  {
+    diagnostics.push(ConfigurationDiagnostic {
+      property_name: "fileHeaderYear".to_string(),
+      message: "Expected a year for the {year} placeholder of fileHeader since the current year isn't available to the Wasm plugin.".to_string(),
+    });
+  }
+
+  // mago sorts the methods after the members are ordered, which would undo
+  // the configured order
+  if resolved_config.sort_class_methods == Some(true)
+    && resolved_config
+      .ordered_class_elements
+      .as_ref()
+      .is_some_and(|groups| !groups.is_empty())
+  {
+    diagnostics.push(ConfigurationDiagnostic {
+      property_name: "sortClassMethods".to_string(),
+      message: "Cannot be used with orderedClassElements, which already orders the methods.".to_string(),
+    });
+  }
+
   diagnostics.extend(get_unknown_property_diagnostics(config));
 
   ResolveConfigurationResult {
@@ -321,3 +426,23 @@ pub fn resolve_config(
     diagnostics,
   }
 }
+
+fn get_array_value<T: std::str::FromStr>(
+  value: ConfigKeyValue,
+  key: &str,
+  index: usize,
+  diagnostics: &mut Vec<ConfigurationDiagnostic>,
+) -> Option<T> {
+  let message = match value {
+    ConfigKeyValue::String(text) => match text.parse() {
+      Ok(value) => return Some(value),
+      Err(_) => format!("Unknown value '{text}' at index {index}."),
+    },
+    _ => format!("Expected a string at index {index}."),
+  };
+  diagnostics.push(ConfigurationDiagnostic {
+    property_name: key.to_string(),
+    message,
+  });
+  None
+}
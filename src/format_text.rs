@@ -3,6 +3,7 @@ use std::path::Path;
 
 use anyhow::Result;
 use mago_allocator::LocalArena;
+use mago_database::file::File;
 use mago_formatter::Formatter;
 use mago_formatter::settings::BraceStyle;
 use mago_formatter::settings::EndOfLine;
@@ -13,8 +14,36 @@ use mago_formatter::settings::SortOrder;
 use mago_php_version::PHPVersion;
 
 use crate::configuration::Configuration;
+use crate::transforms;
+
+const FILE_NAME: &[u8] = b"file.php";
 
 pub fn format_text(file_path: &Path, input_text: &str, config: &Configuration) -> Result<Option<String>> {
+  let arena = LocalArena::new();
+  let settings = MagoSettings::from_config(config);
+  let maybe_bytes = format_bytes(&arena, file_path, input_text.as_bytes().to_vec(), config, &settings)?;
+  match maybe_bytes {
+    Some(bytes) => Ok(Some(String::from_utf8(bytes.into_owned())?)),
+    None => Ok(None),
+  }
+}
+
+/// Formats the bytes of a file, allocating the result in the provided arena.
+///
+/// The settings must be built from the configuration, which lets callers
+/// formatting many files with one configuration build them once.
+///
+/// The input is moved into the parsed file instead of being copied and the
+/// formatted bytes are borrowed from the arena unless an output transform
+/// rewrote them, so callers formatting many files can reuse one arena by
+/// resetting it between calls.
+pub(crate) fn format_bytes<'arena>(
+  arena: &'arena LocalArena,
+  file_path: &Path,
+  input_bytes: Vec<u8>,
+  config: &Configuration,
+  settings: &MagoSettings,
+) -> Result<Option<Cow<'arena, [u8]>>> {
   let lower_ext = file_path
     .extension()
     .and_then(|ext| ext.to_str())
@@ -25,29 +54,55 @@ pub fn format_text(file_path: &Path, input_text: &str, config: &Configuration) -
     return Ok(None);
   }
 
-  let arena = LocalArena::new();
-  let php_version = PHPVersion::new(
-    config.php_version_major.unwrap_or(8) as u32,
-    config.php_version_minor.unwrap_or(4) as u32,
-    0,
-  );
-  let settings = build_format_settings(config);
-  let formatter = Formatter::new(&arena, php_version, settings);
-
-  // mago_formatter::Formatter::format_code requires Cow<'static, [u8]>,
-  // so the runtime inputs are converted to owned Vec<u8>.
-  let file_name = file_path.to_string_lossy().into_owned().into_bytes();
-  let code = input_text.as_bytes().to_vec();
-  let formatted = formatter.format_code(Cow::Owned(file_name), Cow::Owned(code))?;
-  let formatted = std::str::from_utf8(formatted)?;
-
-  if formatted == input_text {
+  let formatter = Formatter::new(arena, settings.php_version, settings.format_settings);
+
+  // keep the original input around only when a transform rewrote it, since
+  // the result is compared against what the caller passed in
+  let (input_bytes, original_bytes) = match transforms::transform_input(file_path, &input_bytes, config) {
+    Some(transformed) => (transformed, Some(input_bytes)),
+    None => (input_bytes, None),
+  };
+
+  // the file name is only used to derive an id for diagnostics, so a static
+  // name avoids copying the path for every file
+  let file = File::ephemeral(Cow::Borrowed(FILE_NAME), Cow::Owned(input_bytes));
+  let formatted = formatter.format_file(&file)?;
+  let formatted = match transforms::transform_output(formatted, config) {
+    Some(transformed) => Cow::Owned(transformed),
+    None => Cow::Borrowed(formatted),
+  };
+
+  if formatted.as_ref() == original_bytes.as_deref().unwrap_or(&file.contents) {
     Ok(None)
   } else {
-    Ok(Some(formatted.to_string()))
+    Ok(Some(formatted))
   }
 }
 
+/// Mago formatter settings built from a [`Configuration`].
+#[derive(Clone, Copy)]
+pub(crate) struct MagoSettings {
+  pub php_version: PHPVersion,
+  pub format_settings: FormatSettings,
+}
+
+impl MagoSettings {
+  pub fn from_config(config: &Configuration) -> Self {
+    MagoSettings {
+      php_version: php_version(config),
+      format_settings: build_format_settings(config),
+    }
+  }
+}
+
+pub(crate) fn php_version(config: &Configuration) -> PHPVersion {
+  PHPVersion::new(
+    config.php_version_major.unwrap_or(8) as u32,
+    config.php_version_minor.unwrap_or(4) as u32,
+    0,
+  )
+}
+
 fn build_format_settings(config: &Configuration) -> FormatSettings {
   let mut settings = FormatSettings::default();
 
@@ -432,4 +487,28 @@ mod test {
     let result = format_text(std::path::Path::new("test.js"), input, &config).unwrap();
     assert!(result.is_none());
   }
+
+  #[test]
+  fn reuses_arena_between_files() {
+    let mut arena = LocalArena::new();
+    let config = crate::configuration::Configuration::default();
+    let path = std::path::Path::new("test.php");
+    let settings = MagoSettings::from_config(&config);
+    let result = format_bytes(&arena, path, b"<?php\necho   'a';\n".to_vec(), &config, &settings).unwrap();
+    assert_eq!(result.as_deref(), Some(&b"<?php\n\necho 'a';\n"[..]));
+    arena.reset();
+    let result = format_bytes(&arena, path, b"<?php\n\necho 'b';\n".to_vec(), &config, &settings).unwrap();
+    assert_eq!(result, None);
+  }
+
+  #[test]
+  fn uses_changed_config() {
+    let path = std::path::Path::new("test.php");
+    let input = "<?php\n\nfoo($alpha, $beta);\n";
+    let mut config = crate::configuration::Configuration::default();
+    assert_eq!(format_text(path, input, &config).unwrap(), None);
+    config.print_width = Some(16);
+    let result = format_text(path, input, &config.clone()).unwrap();
+    assert_eq!(result.as_deref(), Some("<?php\n\nfoo(\n    $alpha,\n    $beta,\n);\n"));
+  }
 }
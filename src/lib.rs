@@ -1,7 +1,23 @@
 pub mod configuration;
 mod format_text;
+mod text_edits;
+mod transforms;
 
 pub use format_text::format_text;
+pub use text_edits::TextEdit;
+pub use text_edits::format_text_edits;
+
+#[cfg(any(
+  all(feature = "wasm", target_arch = "wasm32", target_os = "unknown"),
+  feature = "process"
+))]
+mod plugin_handler;
+
+#[cfg(any(
+  all(feature = "wasm", target_arch = "wasm32", target_os = "unknown"),
+  feature = "process"
+))]
+pub use plugin_handler::MagoPluginHandler;
 
 #[cfg(feature = "wasm")]
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
@@ -10,3 +26,6 @@ mod wasm_plugin;
 #[cfg(feature = "wasm")]
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
 pub use wasm_plugin::*;
+
+#[cfg(feature = "process")]
+mod process_plugin;
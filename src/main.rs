@@ -0,0 +1,24 @@
+use dprint_core::plugins::process::get_parent_process_id_from_cli_args;
+use dprint_core::plugins::process::handle_process_stdio_messages;
+use dprint_core::plugins::process::start_parent_process_checker_task;
+use dprint_plugin_mago::MagoPluginHandler;
+
+fn main() {
+  let runtime = tokio::runtime::Builder::new_current_thread()
+    .enable_time()
+    .build()
+    .unwrap();
+
+  let result = runtime.block_on(async {
+    if let Some(parent_process_id) = get_parent_process_id_from_cli_args() {
+      start_parent_process_checker_task(parent_process_id);
+    }
+
+    handle_process_stdio_messages(MagoPluginHandler).await
+  });
+
+  if let Err(err) = result {
+    eprintln!("Shutting down due to error: {:#}", err);
+    std::process::exit(1);
+  }
+}
@@ -0,0 +1,80 @@
+use std::cell::RefCell;
+use std::path::Path;
+
+use dprint_core::configuration::ConfigKeyMap;
+use dprint_core::configuration::GlobalConfiguration;
+use dprint_core::plugins::FileMatchingInfo;
+use dprint_core::plugins::FormatResult;
+use dprint_core::plugins::PluginInfo;
+use dprint_core::plugins::PluginResolveConfigurationResult;
+use mago_allocator::LocalArena;
+use serde::Serialize;
+
+use crate::configuration::Configuration;
+use crate::configuration::resolve_config;
+use crate::format_text::MagoSettings;
+use crate::format_text::format_bytes;
+
+/// The dprint plugin handler, shared by the Wasm and process plugin builds.
+pub struct MagoPluginHandler;
+
+/// A resolved configuration along with the Mago settings built from it, so
+/// that they're built once per resolution instead of once per file.
+#[derive(Clone, Serialize)]
+pub struct PluginConfiguration {
+  #[serde(flatten)]
+  config: Configuration,
+  #[serde(skip)]
+  settings: MagoSettings,
+}
+
+thread_local! {
+  static ARENA: RefCell<LocalArena> = const { RefCell::new(LocalArena::new()) };
+}
+
+pub(crate) fn resolve_plugin_config(
+  config: ConfigKeyMap,
+  global_config: &GlobalConfiguration,
+) -> PluginResolveConfigurationResult<PluginConfiguration> {
+  let result = resolve_config(config, global_config);
+  let file_extensions = vec!["php".to_string()];
+  PluginResolveConfigurationResult {
+    config: PluginConfiguration {
+      settings: MagoSettings::from_config(&result.config),
+      config: result.config,
+    },
+    diagnostics: result.diagnostics,
+    file_matching: FileMatchingInfo {
+      file_extensions,
+      file_names: vec![],
+    },
+  }
+}
+
+pub(crate) fn plugin_info() -> PluginInfo {
+  let version = env!("CARGO_PKG_VERSION").to_string();
+  PluginInfo {
+    name: env!("CARGO_PKG_NAME").to_string(),
+    version: version.clone(),
+    config_key: "mago".to_string(),
+    help_url: "https://dprint.dev/plugins/mago".to_string(),
+    config_schema_url: format!(
+      "https://plugins.dprint.dev/dprint/dprint-plugin-mago/{}/schema.json",
+      version
+    ),
+    update_url: Some("https://plugins.dprint.dev/dprint/dprint-plugin-mago/latest.json".to_string()),
+  }
+}
+
+pub(crate) fn license_text() -> String {
+  std::str::from_utf8(include_bytes!("../LICENSE")).unwrap().into()
+}
+
+pub(crate) fn format_file_bytes(file_path: &Path, file_bytes: Vec<u8>, config: &PluginConfiguration) -> FormatResult {
+  ARENA.with_borrow_mut(|arena| {
+    // reuse the arena's chunks from the previous file on this thread
+    arena.reset();
+    let maybe_bytes = format_bytes(arena, file_path, file_bytes, &config.config, &config.settings)?;
+    Ok(maybe_bytes.map(|bytes| bytes.into_owned()))
+  })
+}
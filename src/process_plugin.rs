@@ -0,0 +1,48 @@
+use super::plugin_handler::*;
+
+use dprint_core::async_runtime::LocalBoxFuture;
+use dprint_core::async_runtime::async_trait;
+use dprint_core::async_runtime::spawn_blocking;
+use dprint_core::configuration::ConfigKeyMap;
+use dprint_core::configuration::GlobalConfiguration;
+use dprint_core::plugins::AsyncPluginHandler;
+use dprint_core::plugins::FormatRequest;
+use dprint_core::plugins::FormatResult;
+use dprint_core::plugins::HostFormatRequest;
+use dprint_core::plugins::PluginInfo;
+use dprint_core::plugins::PluginResolveConfigurationResult;
+
+#[async_trait(?Send)]
+impl AsyncPluginHandler for MagoPluginHandler {
+  type Configuration = PluginConfiguration;
+
+  fn plugin_info(&self) -> PluginInfo {
+    plugin_info()
+  }
+
+  fn license_text(&self) -> String {
+    license_text()
+  }
+
+  async fn resolve_config(
+    &self,
+    config: ConfigKeyMap,
+    global_config: GlobalConfiguration,
+  ) -> PluginResolveConfigurationResult<PluginConfiguration> {
+    resolve_plugin_config(config, &global_config)
+  }
+
+  async fn format(
+    &self,
+    request: FormatRequest<PluginConfiguration>,
+    _format_with_host: impl FnMut(HostFormatRequest) -> LocalBoxFuture<'static, FormatResult> + 'static,
+  ) -> FormatResult {
+    if request.range.is_some() {
+      return Ok(None); // range formatting not implemented
+    }
+
+    // the message loop runs on a single thread, so format on the blocking
+    // pool in order to format several files in parallel
+    spawn_blocking(move || format_file_bytes(&request.file_path, request.file_bytes, &request.config)).await?
+  }
+}
@@ -0,0 +1,268 @@
+use std::ops::Range;
+use std::path::Path;
+
+use anyhow::Result;
+use similar::Algorithm;
+use similar::DiffTag;
+use similar::capture_diff_slices;
+
+use crate::configuration::Configuration;
+use crate::format_text::format_text;
+
+/// A replacement of a byte range in the original text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+  /// The byte range in the original text to replace.
+  pub range: Range<usize>,
+  /// The text to replace the range with.
+  pub new_text: String,
+}
+
+/// Formats the text and returns the minimal edits that transform the input
+/// into the formatted output.
+///
+/// The edits are sorted, do not overlap, and their ranges refer to byte
+/// offsets in the input text. An empty list means the text is already
+/// formatted.
+pub fn format_text_edits(file_path: &Path, input_text: &str, config: &Configuration) -> Result<Vec<TextEdit>> {
+  match format_text(file_path, input_text, config)? {
+    Some(output_text) => Ok(diff_text(input_text, &output_text)),
+    None => Ok(Vec::new()),
+  }
+}
+
+/// Diffs by line first, then refines each changed block of lines by token so
+/// that only the parts of a line that changed are replaced.
+///
+/// The final newlines are compared separately so that adding or removing one
+/// doesn't replace the unchanged last line.
+fn diff_text(old_text: &str, new_text: &str) -> Vec<TextEdit> {
+  let old_body = old_text.strip_suffix('\n').unwrap_or(old_text);
+  let new_body = new_text.strip_suffix('\n').unwrap_or(new_text);
+  let mut edits = diff_lines(old_body, new_body);
+
+  let end = old_body.len();
+  match (old_body.len() < old_text.len(), new_body.len() < new_text.len()) {
+    (false, true) => match edits.last_mut() {
+      Some(last) if last.range.end == end => last.new_text.push('\n'),
+      _ => edits.push(TextEdit {
+        range: end..end,
+        new_text: "\n".to_string(),
+      }),
+    },
+    (true, false) => match edits.last_mut() {
+      Some(last) if last.range.end == end => last.range.end += 1,
+      _ => edits.push(TextEdit {
+        range: end..end + 1,
+        new_text: String::new(),
+      }),
+    },
+    _ => {}
+  }
+  edits
+}
+
+fn diff_lines(old_text: &str, new_text: &str) -> Vec<TextEdit> {
+  let old_lines = split_with_offsets(old_text, |text| text.split_inclusive('\n'));
+  let new_lines = split_with_offsets(new_text, |text| text.split_inclusive('\n'));
+  let mut edits = Vec::new();
+
+  for (old_range, new_range) in changed_ranges(&old_lines, &new_lines) {
+    let old_block = &old_text[old_range.clone()];
+    let new_block = &new_text[new_range.clone()];
+    if old_block.is_empty() || new_block.is_empty() {
+      edits.push(TextEdit {
+        range: old_range,
+        new_text: new_block.to_string(),
+      });
+      continue;
+    }
+
+    let old_tokens = split_with_offsets(old_block, tokenize);
+    let new_tokens = split_with_offsets(new_block, tokenize);
+    for (old_token_range, new_token_range) in changed_ranges(&old_tokens, &new_tokens) {
+      edits.push(TextEdit {
+        range: old_range.start + old_token_range.start..old_range.start + old_token_range.end,
+        new_text: new_block[new_token_range].to_string(),
+      });
+    }
+  }
+
+  edits
+}
+
+/// Gets the byte ranges of each differing run of pieces in the old and new text.
+fn changed_ranges(old: &[(usize, &str)], new: &[(usize, &str)]) -> Vec<(Range<usize>, Range<usize>)> {
+  let old_values = old.iter().map(|(_, value)| *value).collect::<Vec<_>>();
+  let new_values = new.iter().map(|(_, value)| *value).collect::<Vec<_>>();
+  let byte_range = |pieces: &[(usize, &str)], range: Range<usize>| {
+    let start = pieces.get(range.start).map(|(offset, _)| *offset).unwrap_or_else(|| {
+      // empty ranges at the end of the text start after the last piece
+      pieces.last().map(|(offset, value)| offset + value.len()).unwrap_or(0)
+    });
+    let end = if range.is_empty() {
+      start
+    } else {
+      let (offset, value) = pieces[range.end - 1];
+      offset + value.len()
+    };
+    start..end
+  };
+
+  let mut ranges: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+  for op in capture_diff_slices(Algorithm::Myers, &old_values, &new_values) {
+    let (tag, old_range, new_range) = op.as_tag_tuple();
+    if tag == DiffTag::Equal {
+      continue;
+    }
+    let old_range = byte_range(old, old_range);
+    let new_range = byte_range(new, new_range);
+    // join adjacent deletes and inserts into a single replacement
+    match ranges.last_mut() {
+      Some((last_old, last_new)) if last_old.end == old_range.start && last_new.end == new_range.start => {
+        last_old.end = old_range.end;
+        last_new.end = new_range.end;
+      }
+      _ => ranges.push((old_range, new_range)),
+    }
+  }
+  ranges
+}
+
+fn split_with_offsets<'a, I: Iterator<Item = &'a str>>(
+  text: &'a str,
+  split: impl FnOnce(&'a str) -> I,
+) -> Vec<(usize, &'a str)> {
+  let mut offset = 0;
+  split(text)
+    .map(|piece| {
+      let start = offset;
+      offset += piece.len();
+      (start, piece)
+    })
+    .collect()
+}
+
+/// Splits text into words, runs of horizontal whitespace, newlines, and
+/// individual punctuation characters.
+fn tokenize(text: &str) -> impl Iterator<Item = &str> {
+  #[derive(PartialEq)]
+  enum Kind {
+    Word,
+    Space,
+    Other,
+  }
+
+  fn kind(c: char) -> Kind {
+    if c.is_alphanumeric() || c == '_' || c == '$' {
+      Kind::Word
+    } else if c.is_whitespace() && c != '\n' {
+      Kind::Space
+    } else {
+      Kind::Other
+    }
+  }
+
+  let mut remaining = text;
+  std::iter::from_fn(move || {
+    let mut chars = remaining.char_indices();
+    let (_, first) = chars.next()?;
+    let first_kind = kind(first);
+    let end = if first_kind == Kind::Other {
+      first.len_utf8()
+    } else {
+      chars
+        .find(|(_, c)| kind(*c) != first_kind)
+        .map(|(index, _)| index)
+        .unwrap_or(remaining.len())
+    };
+    let (token, rest) = remaining.split_at(end);
+    remaining = rest;
+    Some(token)
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn apply_edits(text: &str, edits: &[TextEdit]) -> String {
+    let mut result = text.to_string();
+    for edit in edits.iter().rev() {
+      result.replace_range(edit.range.clone(), &edit.new_text);
+    }
+    result
+  }
+
+  #[test]
+  fn diffs_changed_tokens_within_lines() {
+    let old_text = "<?php\n\nfunction test($a,$b)\n{\n    return 1;\n}\n";
+    let new_text = "<?php\n\nfunction test($a, $b)\n{\n    return 1;\n}\n";
+    let edits = diff_text(old_text, new_text);
+    assert_eq!(
+      edits,
+      vec![TextEdit {
+        range: 24..24,
+        new_text: " ".to_string(),
+      }]
+    );
+  }
+
+  #[test]
+  fn diffs_inserted_and_removed_lines() {
+    let old_text = "a\nb\nc\n\n\nd";
+    let new_text = "a\nx\nb\nc\n\nd\n";
+    let edits = diff_text(old_text, new_text);
+    assert_eq!(apply_edits(old_text, &edits), new_text);
+    assert_eq!(
+      edits,
+      vec![
+        TextEdit {
+          range: 2..2,
+          new_text: "x\n".to_string(),
+        },
+        TextEdit {
+          range: 6..7,
+          new_text: String::new(),
+        },
+        TextEdit {
+          range: 9..9,
+          new_text: "\n".to_string(),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn diffs_removed_final_newline() {
+    let old_text = "a\nb\n";
+    let new_text = "a\nc";
+    let edits = diff_text(old_text, new_text);
+    assert_eq!(apply_edits(old_text, &edits), new_text);
+    assert_eq!(
+      edits,
+      vec![TextEdit {
+        range: 2..4,
+        new_text: "c".to_string(),
+      }]
+    );
+  }
+
+  #[test]
+  fn formats_to_edits() {
+    let input = "<?php\nif($a==1){echo 'one';}\n";
+    let config = Configuration::default();
+    let edits = format_text_edits(Path::new("test.php"), input, &config).unwrap();
+    let expected = format_text(Path::new("test.php"), input, &config).unwrap().unwrap();
+    assert_eq!(apply_edits(input, &edits), expected);
+    assert!(edits.iter().all(|edit| edit.range.start >= 6));
+  }
+
+  #[test]
+  fn returns_no_edits_when_formatted() {
+    let input = "<?php\n\necho 'hello';\n";
+    let config = Configuration::default();
+    let edits = format_text_edits(Path::new("test.php"), input, &config).unwrap();
+    assert!(edits.is_empty());
+  }
+}
@@ -0,0 +1,233 @@
+use mago_allocator::LocalArena;
+use mago_span::HasSpan;
+use mago_span::Span;
+use mago_syntax::cst::ForBody;
+use mago_syntax::cst::ForeachBody;
+use mago_syntax::cst::If;
+use mago_syntax::cst::IfBody;
+use mago_syntax::cst::InlineKind;
+use mago_syntax::cst::Keyword;
+use mago_syntax::cst::Node;
+use mago_syntax::cst::Program;
+use mago_syntax::cst::Statement;
+use mago_syntax::cst::SwitchBody;
+use mago_syntax::cst::Terminator;
+use mago_syntax::cst::WhileBody;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use crate::configuration::AlternativeSyntax;
+
+use super::parse;
+
+/// Converts `if`, `for`, `foreach`, `while` and `switch` statements between
+/// braces and the alternative syntax (`if (...): ... endif;`).
+///
+/// With `alternative-in-templates`, files with inline HTML get the
+/// alternative syntax and other files get braces. Only statements whose
+/// bodies are all blocks are converted to the alternative syntax.
+pub fn transform(text: &[u8], style: AlternativeSyntax) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+
+  let edits = match style {
+    AlternativeSyntax::AlternativeInTemplates if is_template(program) => alternative_edits(program),
+    AlternativeSyntax::Braces | AlternativeSyntax::AlternativeInTemplates => brace_edits(program),
+    AlternativeSyntax::Preserve => return None,
+  };
+
+  let mut editor = TextEditor::new(text);
+  for (span, replacement) in edits {
+    editor.apply(
+      TextEdit::replace(span.start.offset..span.end.offset, replacement),
+      None::<fn(&[u8]) -> bool>,
+    );
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
+
+/// Gets whether the file has inline HTML, ignoring whitespace between tags.
+fn is_template(program: &Program) -> bool {
+  Node::Program(program)
+    .filter_map(|node| match node {
+      Node::Inline(inline) if inline.kind == InlineKind::Text => Some(inline.value),
+      _ => None,
+    })
+    .iter()
+    .any(|value| !value.iter().all(u8::is_ascii_whitespace))
+}
+
+fn brace_edits(program: &Program) -> Vec<(Span, &'static str)> {
+  let edits = Node::Program(program).filter_map(|node| {
+    let mut edits = Vec::new();
+    match node {
+      Node::If(r#if) => {
+        let IfBody::ColonDelimited(body) = &r#if.body else {
+          return None;
+        };
+        edits.push((body.colon, " {"));
+        for clause in body.else_if_clauses.iter() {
+          edits.push((clause.elseif.span.to_end(clause.elseif.span.start), "} "));
+          edits.push((clause.colon, " {"));
+        }
+        if let Some(clause) = &body.else_clause {
+          edits.push((clause.r#else.span.to_end(clause.r#else.span.start), "} "));
+          edits.push((clause.colon, " {"));
+        }
+        edits.push((closing_span(&body.endif, &body.terminator), "}"));
+      }
+      Node::For(r#for) => {
+        let ForBody::ColonDelimited(body) = &r#for.body else {
+          return None;
+        };
+        edits.push((body.colon, " {"));
+        edits.push((closing_span(&body.end_for, &body.terminator), "}"));
+      }
+      Node::Foreach(foreach) => {
+        let ForeachBody::ColonDelimited(body) = &foreach.body else {
+          return None;
+        };
+        edits.push((body.colon, " {"));
+        edits.push((closing_span(&body.end_foreach, &body.terminator), "}"));
+      }
+      Node::While(r#while) => {
+        let WhileBody::ColonDelimited(body) = &r#while.body else {
+          return None;
+        };
+        edits.push((body.colon, " {"));
+        edits.push((closing_span(&body.end_while, &body.terminator), "}"));
+      }
+      Node::Switch(switch) => {
+        let SwitchBody::ColonDelimited(body) = &switch.body else {
+          return None;
+        };
+        edits.push((body.colon, " {"));
+        edits.push((closing_span(&body.end_switch, &body.terminator), "}"));
+      }
+      _ => return None,
+    }
+    Some(edits)
+  });
+
+  edits.into_iter().flatten().collect()
+}
+
+/// Gets the span of the end keyword along with its semicolon, keeping a
+/// closing tag that terminates it.
+fn closing_span(keyword: &Keyword, terminator: &Terminator) -> Span {
+  match terminator {
+    Terminator::Semicolon(semicolon) => keyword.span.join(*semicolon),
+    _ => keyword.span,
+  }
+}
+
+fn alternative_edits(program: &Program) -> Vec<(Span, &'static str)> {
+  let text = program.source_text;
+  let edits = Node::Program(program).filter_map(|node| {
+    let mut edits = Vec::new();
+    match node {
+      Node::If(r#if) => {
+        // an `else if` that continues a converted chain is part of its edits
+        let edits = alternative_if_edits(text, r#if)?;
+        return Some((Some(r#if.span().start.offset), edits, chained_if(r#if)));
+      }
+      Node::For(r#for) => {
+        let ForBody::Statement(Statement::Block(block)) = &r#for.body else {
+          return None;
+        };
+        edits.push((block.left_brace, ":"));
+        edits.push((block.right_brace, "endfor;"));
+      }
+      Node::Foreach(foreach) => {
+        let ForeachBody::Statement(Statement::Block(block)) = &foreach.body else {
+          return None;
+        };
+        edits.push((block.left_brace, ":"));
+        edits.push((block.right_brace, "endforeach;"));
+      }
+      Node::While(r#while) => {
+        let WhileBody::Statement(Statement::Block(block)) = &r#while.body else {
+          return None;
+        };
+        edits.push((block.left_brace, ":"));
+        edits.push((block.right_brace, "endwhile;"));
+      }
+      Node::Switch(switch) => {
+        let SwitchBody::BraceDelimited(body) = &switch.body else {
+          return None;
+        };
+        edits.push((body.left_brace, ":"));
+        edits.push((body.right_brace, "endswitch;"));
+      }
+      _ => return None,
+    }
+    Some((None, edits, None))
+  });
+
+  let chained = edits.iter().filter_map(|(_, _, chained)| *chained).collect::<Vec<_>>();
+  edits
+    .into_iter()
+    .filter(|(start, _, _)| start.is_none_or(|start| !chained.contains(&start)))
+    .flat_map(|(_, edits, _)| edits)
+    .collect()
+}
+
+/// Gets the edits that convert an if statement to the alternative syntax,
+/// joining an `else if` into `elseif`, or `None` when a body isn't a block.
+fn alternative_if_edits(text: &[u8], r#if: &If) -> Option<Vec<(Span, &'static str)>> {
+  let IfBody::Statement(body) = &r#if.body else {
+    return None;
+  };
+  let Statement::Block(block) = body.statement else {
+    return None;
+  };
+  let mut edits = vec![(block.left_brace, ":")];
+  let mut last = block;
+  for clause in body.else_if_clauses.iter() {
+    let Statement::Block(block) = clause.statement else {
+      return None;
+    };
+    edits.push((last.right_brace, ""));
+    edits.push((block.left_brace, ":"));
+    last = block;
+  }
+
+  match body.else_clause.as_ref().map(|clause| (clause, clause.statement)) {
+    Some((_, Statement::Block(block))) => {
+      edits.push((last.right_brace, ""));
+      edits.push((block.left_brace, ":"));
+      last = block;
+    }
+    Some((clause, Statement::If(inner))) => {
+      let between = &text[clause.r#else.span.end.offset as usize..inner.r#if.span.start.offset as usize];
+      if !between.iter().all(u8::is_ascii_whitespace) {
+        return None;
+      }
+      edits.push((last.right_brace, ""));
+      edits.push((Span::between(clause.r#else.span, inner.r#if.span), "elseif"));
+      edits.extend(alternative_if_edits(text, inner)?);
+      return Some(edits);
+    }
+    Some(_) => return None,
+    None => {}
+  }
+
+  edits.push((last.right_brace, "endif;"));
+  Some(edits)
+}
+
+/// Gets the start of the `if` in an `else if` of the statement.
+fn chained_if(r#if: &If) -> Option<u32> {
+  let IfBody::Statement(body) = &r#if.body else {
+    return None;
+  };
+  match body.else_clause.as_ref()?.statement {
+    Statement::If(inner) => Some(inner.span().start.offset),
+    _ => None,
+  }
+}
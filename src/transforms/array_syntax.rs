@@ -0,0 +1,145 @@
+use mago_allocator::LocalArena;
+use mago_span::Span;
+use mago_syntax::cst::ArrayElement;
+use mago_syntax::cst::ForeachTarget;
+use mago_syntax::cst::Node;
+use mago_syntax::cst::TokenSeparatedSequence;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use crate::configuration::ArraySyntax;
+
+use super::parse;
+
+/// Rewrites array literals to the `array_syntax` style and destructuring
+/// assignments to the `list_syntax` style.
+///
+/// Only the delimiters are replaced, so comments and the elements stay as
+/// they are and the formatter lays out the result.
+pub fn transform(text: &[u8], array_syntax: ArraySyntax, list_syntax: ArraySyntax) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut rewriter = Rewriter {
+    text,
+    array_syntax,
+    list_syntax,
+    edits: Vec::new(),
+  };
+  rewriter.visit(Node::Program(program), false);
+
+  if rewriter.edits.is_empty() {
+    return None;
+  }
+
+  let mut editor = TextEditor::new(text);
+  for edit in rewriter.edits {
+    editor.apply(edit, None::<fn(&[u8]) -> bool>);
+  }
+  Some(editor.finish())
+}
+
+struct Rewriter<'a> {
+  text: &'a [u8],
+  array_syntax: ArraySyntax,
+  list_syntax: ArraySyntax,
+  edits: Vec<TextEdit>,
+}
+
+impl Rewriter<'_> {
+  /// Visits the node, where `destructuring` tells whether an array literal at
+  /// this position is the target of a destructuring assignment.
+  fn visit(&mut self, node: Node, destructuring: bool) {
+    match node {
+      Node::Expression(_) => {
+        node.visit_children(|child| self.visit(child, destructuring));
+      }
+      Node::Assignment(assignment) if assignment.operator.is_assign() => {
+        self.visit(Node::Expression(assignment.lhs), true);
+        self.visit(Node::Expression(assignment.rhs), false);
+      }
+      Node::ForeachTarget(target) => {
+        let value = match target {
+          ForeachTarget::Value(target) => target.value,
+          ForeachTarget::KeyValue(target) => {
+            self.visit(Node::Expression(target.key), false);
+            target.value
+          }
+        };
+        self.visit(Node::Expression(value), true);
+      }
+      Node::Array(array) if destructuring => {
+        if self.list_syntax == ArraySyntax::Long {
+          self.replace_open(array.left_bracket, "list(");
+          self.replace(array.right_bracket, ")");
+        }
+        self.visit_destructuring_elements(&array.elements);
+      }
+      Node::List(list) => {
+        if self.list_syntax == ArraySyntax::Short {
+          self
+            .edits
+            .push(TextEdit::delete(list.list.span.start.offset..list.list.span.end.offset));
+          self.replace(list.left_parenthesis, "[");
+          self.replace(list.right_parenthesis, "]");
+        }
+        self.visit_destructuring_elements(&list.elements);
+      }
+      Node::Array(array) => {
+        if self.array_syntax == ArraySyntax::Long {
+          self.replace_open(array.left_bracket, "array(");
+          self.replace(array.right_bracket, ")");
+        }
+        node.visit_children(|child| self.visit(child, false));
+      }
+      Node::LegacyArray(array) => {
+        if self.array_syntax == ArraySyntax::Short {
+          self.edits.push(TextEdit::delete(
+            array.array.span.start.offset..array.array.span.end.offset,
+          ));
+          self.replace(array.left_parenthesis, "[");
+          self.replace(array.right_parenthesis, "]");
+        }
+        node.visit_children(|child| self.visit(child, false));
+      }
+      _ => node.visit_children(|child| self.visit(child, false)),
+    }
+  }
+
+  /// Visits the elements of a destructuring target, whose values may be
+  /// nested destructuring targets.
+  fn visit_destructuring_elements(&mut self, elements: &TokenSeparatedSequence<ArrayElement>) {
+    for element in elements.iter() {
+      match element {
+        ArrayElement::KeyValue(element) => {
+          self.visit(Node::Expression(element.key), false);
+          self.visit(Node::Expression(element.value), true);
+        }
+        ArrayElement::Value(element) => self.visit(Node::Expression(element.value), true),
+        _ => self.visit(Node::ArrayElement(element), false),
+      }
+    }
+  }
+
+  fn replace(&mut self, span: Span, text: &str) {
+    self
+      .edits
+      .push(TextEdit::replace(span.start.offset..span.end.offset, text));
+  }
+
+  /// Replaces an opening bracket with a keyword, separating it from a
+  /// preceding keyword (ex. `return[1]`).
+  fn replace_open(&mut self, span: Span, text: &str) {
+    let start = span.start.offset as usize;
+    let follows_word = start > 0 && is_word_byte(self.text[start - 1]);
+    let text = if follows_word {
+      format!(" {text}")
+    } else {
+      text.to_string()
+    };
+    self.replace(span, &text);
+  }
+}
+
+fn is_word_byte(byte: u8) -> bool {
+  byte.is_ascii_alphanumeric() || byte == b'_' || byte >= 0x80
+}
@@ -0,0 +1,99 @@
+use mago_allocator::LocalArena;
+use mago_span::HasSpan;
+use mago_span::Span;
+use mago_syntax::cst::Hint;
+use mago_syntax::cst::Node;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use super::parse;
+
+/// The kinds of names whose casing is normalised.
+#[derive(Clone, Copy)]
+pub struct CasingOptions {
+  pub lowercase_keywords: bool,
+  pub lowercase_native_types: bool,
+  pub uppercase_magic_constants: bool,
+}
+
+/// Normalises the casing of keywords, native type declarations and magic
+/// constants, which PHP treats case-insensitively.
+///
+/// `true`, `false` and `null` in expressions are left to the
+/// `uppercaseLiteralKeyword` setting.
+pub fn transform(text: &[u8], options: CasingOptions) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut spans = Vec::new();
+  collect(Node::Program(program), options, &mut spans);
+
+  let mut editor = TextEditor::new(text);
+  for (span, uppercase) in spans {
+    let value = &text[span.start.offset as usize..span.end.offset as usize];
+    let normalized = if uppercase {
+      value.to_ascii_uppercase()
+    } else {
+      value.to_ascii_lowercase()
+    };
+    if normalized != value {
+      editor.apply(
+        TextEdit::replace(span.start.offset..span.end.offset, normalized),
+        None::<fn(&[u8]) -> bool>,
+      );
+    }
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
+
+/// Collects the spans to change along with whether they're uppercased.
+fn collect(node: Node, options: CasingOptions, spans: &mut Vec<(Span, bool)>) {
+  match node {
+    Node::Literal(_) => return,
+    Node::Hint(hint) if is_native_type(hint) => {
+      if options.lowercase_native_types {
+        spans.push((hint.span(), false));
+      }
+      return;
+    }
+    Node::MagicConstant(constant) => {
+      if options.uppercase_magic_constants {
+        spans.push((constant.span(), true));
+      }
+      return;
+    }
+    Node::Keyword(keyword) => {
+      if options.lowercase_keywords {
+        spans.push((keyword.span, false));
+      }
+      return;
+    }
+    _ => {}
+  }
+
+  node.visit_children(|child| collect(child, options, spans));
+}
+
+fn is_native_type(hint: &Hint) -> bool {
+  matches!(
+    hint,
+    Hint::Null(_)
+      | Hint::True(_)
+      | Hint::False(_)
+      | Hint::Array(_)
+      | Hint::Callable(_)
+      | Hint::Void(_)
+      | Hint::Never(_)
+      | Hint::Float(_)
+      | Hint::Bool(_)
+      | Hint::Integer(_)
+      | Hint::String(_)
+      | Hint::Object(_)
+      | Hint::Mixed(_)
+      | Hint::Iterable(_)
+  )
+}
@@ -0,0 +1,90 @@
+use mago_allocator::LocalArena;
+use mago_syntax::cst::TriviaKind;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use super::parse;
+
+/// The comment normalisations to apply.
+#[derive(Clone, Copy)]
+pub struct CommentOptions {
+  pub space_after_line_comment: bool,
+  pub convert_single_line_block_comments: bool,
+}
+
+/// Normalises single-line comments: `//` gets a space after it and `/* x */`
+/// comments that end their line become `// x`.
+///
+/// Docblocks are left alone. `#` comments are already printed as `//`
+/// comments by mago.
+pub fn transform(text: &[u8], options: CommentOptions) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut editor = TextEditor::new(text);
+
+  for comment in program.trivia.iter() {
+    let start = comment.span.start.offset as usize;
+    let end = comment.span.end.offset as usize;
+    let (body, add_space) = match comment.kind {
+      TriviaKind::SingleLineComment => {
+        let body = &comment.value[2..];
+        (body, options.space_after_line_comment && needs_space(body))
+      }
+      TriviaKind::MultiLineComment if options.convert_single_line_block_comments => {
+        let Some(body) = single_line_block_body(comment.value) else {
+          continue;
+        };
+        // anything after the comment on its line would be commented out
+        if !ends_line(&text[end..]) {
+          continue;
+        }
+        (body, true)
+      }
+      _ => continue,
+    };
+
+    let mut replacement = b"//".to_vec();
+    if add_space {
+      replacement.push(b' ');
+    }
+    replacement.extend_from_slice(body);
+    if replacement != comment.value {
+      editor.apply(
+        TextEdit::replace(start as u32..end as u32, replacement),
+        None::<fn(&[u8]) -> bool>,
+      );
+    }
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
+
+/// Gets the text of a `/* x */` comment with the spaces around it trimmed, or
+/// `None` when it spans lines or couldn't be a `//` comment.
+fn single_line_block_body(value: &[u8]) -> Option<&[u8]> {
+  let body = value.strip_prefix(b"/*")?.strip_suffix(b"*/")?.trim_ascii();
+  // a `//` comment ends at a closing tag
+  if body.is_empty() || body.contains(&b'\n') || body.windows(2).any(|window| window == b"?>") {
+    return None;
+  }
+  Some(body)
+}
+
+/// Gets whether the text of a line comment starts right after the slashes,
+/// leaving separators such as `////////` alone.
+fn needs_space(body: &[u8]) -> bool {
+  body
+    .first()
+    .is_some_and(|byte| !byte.is_ascii_whitespace() && *byte != b'/')
+}
+
+fn ends_line(rest: &[u8]) -> bool {
+  rest
+    .iter()
+    .find(|byte| !matches!(byte, b' ' | b'\t'))
+    .is_none_or(|byte| matches!(byte, b'\n' | b'\r'))
+}
@@ -0,0 +1,425 @@
+use mago_allocator::LocalArena;
+use mago_syntax::cst::TriviaKind;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use super::parse;
+
+/// Tags whose type, variable and description columns are aligned.
+const ALIGNED_TAGS: [&str; 3] = ["@param", "@return", "@throws"];
+
+pub struct DocblockOptions<'a> {
+  pub print_width: usize,
+  pub tab_width: usize,
+  pub tag_order: &'a [String],
+}
+
+/// Formats the contents of the multi-line docblocks in the text.
+///
+/// This runs on the formatted output so that the final indentation of each
+/// docblock is known when wrapping its lines.
+pub fn transform(text: &[u8], options: &DocblockOptions) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut editor = TextEditor::new(text);
+
+  for comment in program
+    .trivia
+    .iter()
+    .filter(|trivia| trivia.kind == TriviaKind::DocBlockComment)
+  {
+    let start = comment.span.start.offset as usize;
+    let line_start = text[..start]
+      .iter()
+      .rposition(|byte| *byte == b'\n')
+      .map(|index| index + 1)
+      .unwrap_or(0);
+    // docblocks sharing a line with code are left alone
+    let Ok(indent) = std::str::from_utf8(&text[line_start..start]) else {
+      continue;
+    };
+    let Ok(value) = std::str::from_utf8(comment.value) else {
+      continue;
+    };
+    if !indent.chars().all(|c| c == ' ' || c == '\t') {
+      continue;
+    }
+
+    if let Some(formatted) = format_docblock(value, indent, options)
+      && formatted != value
+    {
+      let edit = TextEdit::replace(comment.span.start.offset..comment.span.end.offset, formatted);
+      editor.apply(edit, None::<fn(&[u8]) -> bool>);
+    }
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
+
+fn format_docblock(value: &str, indent: &str, options: &DocblockOptions) -> Option<String> {
+  if !value.contains('\n') {
+    return None;
+  }
+
+  let newline = if value.contains("\r\n") { "\r\n" } else { "\n" };
+  let inner = value.strip_prefix("/**")?.strip_suffix("*/")?;
+  let mut lines = inner.lines().map(strip_gutter).collect::<Vec<_>>();
+  while lines.first().is_some_and(|line| line.is_empty()) {
+    lines.remove(0);
+  }
+  while lines.last().is_some_and(|line| line.is_empty()) {
+    lines.pop();
+  }
+  if lines.is_empty() {
+    return None;
+  }
+
+  let indent_width = indent
+    .chars()
+    .map(|c| if c == '\t' { options.tab_width } else { 1 })
+    .sum::<usize>();
+  // the line width left after the indentation and the ` * ` gutter
+  let available = options.print_width.saturating_sub(indent_width + 3);
+  let lines = format_lines(lines, options.tag_order, available);
+
+  let mut result = String::from("/**");
+  for line in lines {
+    result.push_str(newline);
+    result.push_str(indent);
+    result.push_str(" *");
+    if !line.is_empty() {
+      result.push(' ');
+      result.push_str(&line);
+    }
+  }
+  result.push_str(newline);
+  result.push_str(indent);
+  result.push_str(" */");
+  Some(result)
+}
+
+/// Removes the leading whitespace and `*` of a line along with the space
+/// after it, keeping any further indentation of the content.
+fn strip_gutter(line: &str) -> &str {
+  let trimmed = line.trim_start();
+  let content = match trimmed.strip_prefix('*') {
+    Some(rest) => rest.strip_prefix([' ', '\t']).unwrap_or(rest),
+    None => trimmed,
+  };
+  content.trim_end()
+}
+
+struct Tag<'a> {
+  name: &'a str,
+  lines: Vec<&'a str>,
+  blank_after: bool,
+}
+
+fn format_lines(lines: Vec<&str>, tag_order: &[String], available: usize) -> Vec<String> {
+  let mut description = Vec::new();
+  let mut tags: Vec<Tag> = Vec::new();
+  let mut in_code = false;
+
+  for line in lines {
+    let starts_tag = !in_code && line.starts_with('@');
+    in_code = toggles_code(line, in_code);
+
+    if starts_tag {
+      let name = line.split_whitespace().next().unwrap_or(line);
+      tags.push(Tag {
+        name,
+        lines: vec![line],
+        blank_after: false,
+      });
+    } else if let Some(tag) = tags.last_mut() {
+      if line.is_empty() && !in_code {
+        tag.blank_after = true;
+      } else {
+        // keep blank lines that are followed by more text in the tag
+        if tag.blank_after {
+          tag.lines.push("");
+          tag.blank_after = false;
+        }
+        tag.lines.push(line);
+      }
+    } else {
+      description.push(line);
+    }
+  }
+
+  sort_tags(&mut tags, tag_order);
+
+  let mut result = Vec::new();
+  let mut in_code = false;
+  for line in description {
+    if in_code || line.starts_with("```") {
+      result.push(line.to_string());
+    } else {
+      let hanging = &line[..line.len() - line.trim_start().len()];
+      result.extend(wrap(line.to_string(), first_word_end(line), hanging, available));
+    }
+    in_code = toggles_code(line, in_code);
+  }
+
+  let columns = Columns::new(&tags);
+  for (index, tag) in tags.iter().enumerate() {
+    result.extend(format_tag(tag, &columns, available));
+    if tag.blank_after && index + 1 < tags.len() {
+      result.push(String::new());
+    }
+  }
+
+  result
+}
+
+/// Gets whether the lines after this one are inside a code sample.
+pub(super) fn toggles_code(line: &str, in_code: bool) -> bool {
+  let trimmed = line.trim_start();
+  if trimmed.starts_with("```") {
+    !in_code
+  } else if trimmed.contains("<code>") && !trimmed.contains("</code>") {
+    true
+  } else if trimmed.contains("</code>") {
+    false
+  } else {
+    in_code
+  }
+}
+
+/// Orders the tags by the configured tag names, keeping the original order of
+/// tags with the same name and placing unlisted tags last.
+fn sort_tags(tags: &mut [Tag], tag_order: &[String]) {
+  if tag_order.is_empty() {
+    return;
+  }
+
+  let position = |name: &str| {
+    let name = name.trim_start_matches('@');
+    tag_order
+      .iter()
+      .position(|tag| tag.trim_start_matches('@').eq_ignore_ascii_case(name))
+      .unwrap_or(tag_order.len())
+  };
+  if tags.is_sorted_by_key(|tag| position(tag.name)) {
+    return;
+  }
+
+  // the original blank lines no longer separate the same tags, so separate
+  // each run of tags with the same name instead
+  let had_blank_lines = tags.iter().any(|tag| tag.blank_after);
+  tags.sort_by_key(|tag| position(tag.name));
+  for index in 0..tags.len() {
+    let next_name = tags.get(index + 1).map(|tag| tag.name);
+    tags[index].blank_after = had_blank_lines && next_name.is_some_and(|name| name != tags[index].name);
+  }
+}
+
+struct AlignedTag<'a> {
+  name: &'a str,
+  r#type: &'a str,
+  variable: Option<&'a str>,
+  description: &'a str,
+}
+
+struct Columns {
+  name: usize,
+  r#type: usize,
+  variable: usize,
+}
+
+impl Columns {
+  fn new(tags: &[Tag]) -> Self {
+    let aligned = tags
+      .iter()
+      .filter_map(|tag| parse_aligned_tag(tag.lines[0]))
+      .collect::<Vec<_>>();
+    let width = |texts: &mut dyn Iterator<Item = &str>| texts.map(|text| text.chars().count()).max().unwrap_or(0);
+    Columns {
+      name: width(&mut aligned.iter().map(|tag| tag.name)),
+      r#type: width(&mut aligned.iter().map(|tag| tag.r#type)),
+      variable: width(&mut aligned.iter().filter_map(|tag| tag.variable)),
+    }
+  }
+
+  fn description(&self) -> usize {
+    self.name + 1 + self.r#type + 1 + if self.variable > 0 { self.variable + 1 } else { 0 }
+  }
+}
+
+fn format_tag(tag: &Tag, columns: &Columns, available: usize) -> Vec<String> {
+  // the continuation lines of a multi-line type keep their own indentation
+  if opens_type(tag.lines[0]) {
+    return tag.lines.iter().map(|line| line.to_string()).collect();
+  }
+
+  let mut result = Vec::new();
+  let mut in_code = false;
+
+  let hanging = match parse_aligned_tag(tag.lines[0]) {
+    Some(aligned) => {
+      let mut line = format!(
+        "{:name$} {:type$}",
+        aligned.name,
+        aligned.r#type,
+        name = columns.name,
+        type = columns.r#type
+      );
+      if columns.variable > 0 && (aligned.variable.is_some() || !aligned.description.is_empty()) {
+        line.push_str(&format!(
+          " {:variable$}",
+          aligned.variable.unwrap_or(""),
+          variable = columns.variable
+        ));
+      }
+      // the description is only broken after its first word
+      let min_break = line.len() + 1 + first_word_end(aligned.description);
+      if !aligned.description.is_empty() {
+        line.push(' ');
+        line.push_str(aligned.description);
+      }
+      let hanging = " ".repeat(columns.description());
+      result.extend(wrap(line.trim_end().to_string(), min_break, &hanging, available));
+      hanging
+    }
+    None => {
+      let hanging = " ".repeat(tag.name.chars().count() + 1);
+      result.extend(wrap(
+        tag.lines[0].to_string(),
+        first_word_end(tag.lines[0]),
+        &hanging,
+        available,
+      ));
+      hanging
+    }
+  };
+
+  for line in &tag.lines[1..] {
+    if in_code || line.is_empty() || line.trim_start().starts_with("```") {
+      result.push(line.to_string());
+    } else {
+      let line = format!("{}{}", hanging, line.trim_start());
+      let min_break = first_word_end(&line);
+      result.extend(wrap(line, min_break, &hanging, available));
+    }
+    in_code = toggles_code(line, in_code);
+  }
+
+  result
+}
+
+fn parse_aligned_tag(line: &str) -> Option<AlignedTag<'_>> {
+  let name = line.split_whitespace().next()?;
+  if !ALIGNED_TAGS.contains(&name) {
+    return None;
+  }
+
+  let rest = line[name.len()..].trim_start();
+  if rest.is_empty() || is_variable(rest) {
+    return None;
+  }
+  let (r#type, rest) = split_type(rest)?;
+  let rest = rest.trim_start();
+
+  let (variable, description) = if name == "@param" {
+    let variable = rest.split_whitespace().next().filter(|word| is_variable(word))?;
+    (Some(variable), rest[variable.len()..].trim_start())
+  } else {
+    (None, rest)
+  };
+
+  Some(AlignedTag {
+    name,
+    r#type,
+    variable,
+    description,
+  })
+}
+
+/// Gets whether the type of a tag line continues on the following lines, as
+/// in `@return array{`.
+fn opens_type(line: &str) -> bool {
+  let name = line.split_whitespace().next().unwrap_or(line);
+  let rest = line[name.len()..].trim_start();
+  !rest.is_empty() && split_type(rest).is_none()
+}
+
+fn is_variable(text: &str) -> bool {
+  text.trim_start_matches(['&', '.']).starts_with('$')
+}
+
+/// Splits a type off the start of the text, keeping spaces that are nested in
+/// generics, shapes or callable signatures, or returns `None` when the type
+/// isn't closed by the end of the text.
+fn split_type(text: &str) -> Option<(&str, &str)> {
+  let mut depth = 0usize;
+  let mut end = text.len();
+  let mut previous = ' ';
+  for (index, c) in text.char_indices() {
+    match c {
+      '<' | '(' | '{' | '[' => depth += 1,
+      '>' if previous != '=' && previous != '-' => depth = depth.saturating_sub(1),
+      ')' | '}' | ']' => depth = depth.saturating_sub(1),
+      // callable return types follow a colon and a space
+      c if c.is_whitespace() && depth == 0 && previous != ':' => {
+        end = index;
+        break;
+      }
+      _ => {}
+    }
+    if !c.is_whitespace() {
+      previous = c;
+    }
+  }
+  if end == text.len() && depth > 0 {
+    return None;
+  }
+  Some(text.split_at(end))
+}
+
+/// Breaks the line at spaces after `min_break` so that each part fits in the
+/// available width, indenting the following parts with the hanging indent.
+/// Words longer than the width, such as URLs, are never split.
+fn wrap(line: String, min_break: usize, hanging: &str, available: usize) -> Vec<String> {
+  let mut result = Vec::new();
+  let mut current = line;
+  let mut min_break = min_break;
+
+  while current.chars().count() > available && available > hanging.len() {
+    let mut spaces = current
+      .char_indices()
+      .filter(|(index, c)| *c == ' ' && *index > min_break)
+      .map(|(index, _)| index)
+      .peekable();
+    let first_space = spaces.peek().copied();
+    let break_at = spaces
+      .take_while(|index| current[..*index].chars().count() <= available)
+      .last()
+      .or(first_space);
+    let Some(break_at) = break_at else {
+      break;
+    };
+
+    let rest = current[break_at..].trim_start();
+    if rest.is_empty() {
+      break;
+    }
+    let next = format!("{hanging}{rest}");
+    current.truncate(break_at);
+    result.push(current.trim_end().to_string());
+    current = next;
+    min_break = first_word_end(&current);
+  }
+
+  result.push(current);
+  result
+}
+
+/// Gets the byte offset after the first word of the line.
+fn first_word_end(line: &str) -> usize {
+  let start = line.len() - line.trim_start().len();
+  line[start..].find(' ').map(|index| start + index).unwrap_or(line.len())
+}
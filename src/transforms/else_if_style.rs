@@ -0,0 +1,67 @@
+use mago_allocator::LocalArena;
+use mago_span::Span;
+use mago_syntax::cst::IfBody;
+use mago_syntax::cst::Node;
+use mago_syntax::cst::Statement;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use crate::configuration::ElseIfStyle;
+
+use super::parse;
+
+/// Rewrites the else-if clauses of braced if statements between `elseif` and
+/// `else if`, which PHP treats the same.
+///
+/// Alternative syntax is left alone since it only allows `elseif`, as is an
+/// `else if` with a comment between the two keywords.
+pub fn transform(text: &[u8], style: ElseIfStyle) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut editor = TextEditor::new(text);
+
+  let spans = Node::Program(program).filter_map(|node| {
+    let Node::If(r#if) = node else {
+      return None;
+    };
+    let IfBody::Statement(body) = &r#if.body else {
+      return None;
+    };
+    let spans = match style {
+      ElseIfStyle::Elseif => {
+        let clause = body.else_clause.as_ref()?;
+        let Statement::If(inner) = clause.statement else {
+          return None;
+        };
+        if !matches!(inner.body, IfBody::Statement(_)) {
+          return None;
+        }
+        let between = &text[clause.r#else.span.end.offset as usize..inner.r#if.span.start.offset as usize];
+        if !between.iter().all(u8::is_ascii_whitespace) {
+          return None;
+        }
+        vec![Span::between(clause.r#else.span, inner.r#if.span)]
+      }
+      ElseIfStyle::ElseIf => body.else_if_clauses.iter().map(|clause| clause.elseif.span).collect(),
+      ElseIfStyle::Preserve => return None,
+    };
+    Some(spans)
+  });
+
+  let replacement = match style {
+    ElseIfStyle::Elseif => "elseif",
+    _ => "else if",
+  };
+  for span in spans.into_iter().flatten() {
+    editor.apply(
+      TextEdit::replace(span.start.offset..span.end.offset, replacement),
+      None::<fn(&[u8]) -> bool>,
+    );
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
@@ -0,0 +1,115 @@
+use mago_allocator::LocalArena;
+use mago_span::HasSpan;
+use mago_span::Span;
+use mago_syntax::cst::ClassLikeMember;
+use mago_syntax::cst::Modifier;
+use mago_syntax::cst::Node;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use super::parse;
+
+/// The kinds of class-like members that get an explicit visibility.
+#[derive(Clone, Copy)]
+pub struct VisibilityKinds {
+  pub properties: bool,
+  pub methods: bool,
+  pub constants: bool,
+}
+
+/// Adds `public` to class-like members without a visibility modifier,
+/// replacing the `var` keyword of properties, and puts modifiers in the order
+/// `final`/`abstract`, visibility, `static`, `readonly`.
+///
+/// Modifiers with comments between them are left alone.
+pub fn transform(text: &[u8], kinds: VisibilityKinds) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut editor = TextEditor::new(text);
+
+  let members = Node::Program(program).filter_map(|node| match node {
+    Node::ClassLikeMember(member) => Some(*member),
+    _ => None,
+  });
+  for member in members {
+    let (modifiers, keyword, add_public) = match member {
+      ClassLikeMember::Property(property) => {
+        if let Some(var) = property.var() {
+          if kinds.properties {
+            editor.apply(
+              TextEdit::replace(var.span.start.offset..var.span.end.offset, "public"),
+              None::<fn(&[u8]) -> bool>,
+            );
+          }
+          continue;
+        }
+        (property.modifiers().as_slice(), None, kinds.properties)
+      }
+      ClassLikeMember::Method(method) => (method.modifiers.as_slice(), Some(method.function.span), kinds.methods),
+      ClassLikeMember::Constant(constant) => (
+        constant.modifiers.as_slice(),
+        Some(constant.r#const.span),
+        kinds.constants,
+      ),
+      _ => continue,
+    };
+
+    if let Some(edit) = modifiers_edit(text, modifiers, keyword, add_public) {
+      editor.apply(edit, None::<fn(&[u8]) -> bool>);
+    }
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
+
+/// Gets the edit that adds a missing `public` and orders the modifiers, where
+/// the keyword is what a `public` goes before when there are no modifiers.
+fn modifiers_edit(text: &[u8], modifiers: &[Modifier], keyword: Option<Span>, add_public: bool) -> Option<TextEdit> {
+  let has_visibility = modifiers.iter().any(|modifier| rank(modifier) == 1);
+  let Some((first, last)) = modifiers.first().zip(modifiers.last()) else {
+    let keyword = keyword.filter(|_| add_public)?;
+    return Some(TextEdit::insert(keyword.start.offset, "public "));
+  };
+
+  let start = first.span().start.offset;
+  let end = last.span().end.offset;
+  let current = &text[start as usize..end as usize];
+  let mut ranked = Vec::with_capacity(modifiers.len() + 1);
+  for modifier in modifiers {
+    let span = modifier.span();
+    ranked.push((
+      rank(modifier),
+      &text[span.start.offset as usize..span.end.offset as usize],
+    ));
+  }
+  let words = ranked.iter().map(|(_, word)| word.len()).sum::<usize>();
+  // anything other than spaces between the modifiers is a comment
+  if current.iter().filter(|byte| !byte.is_ascii_whitespace()).count() != words {
+    return None;
+  }
+  if add_public && !has_visibility {
+    ranked.push((1, b"public"));
+  }
+  ranked.sort_by_key(|(rank, _)| *rank);
+
+  let ordered = ranked.iter().map(|(_, word)| *word).collect::<Vec<_>>().join(&b' ');
+  if ordered == current {
+    None
+  } else {
+    Some(TextEdit::replace(start..end, ordered))
+  }
+}
+
+fn rank(modifier: &Modifier) -> u8 {
+  match modifier {
+    Modifier::Final(_) | Modifier::Abstract(_) => 0,
+    Modifier::Public(_) | Modifier::Protected(_) | Modifier::Private(_) => 1,
+    Modifier::PublicSet(_) | Modifier::ProtectedSet(_) | Modifier::PrivateSet(_) => 2,
+    Modifier::Static(_) => 3,
+    Modifier::Readonly(_) => 4,
+  }
+}
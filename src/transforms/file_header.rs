@@ -0,0 +1,195 @@
+use mago_allocator::LocalArena;
+use mago_syntax::cst::Trivia;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use crate::configuration::FileHeaderMode;
+
+use super::file_header;
+use super::parse;
+
+const YEAR_PLACEHOLDER: &str = "{year}";
+const FILE_PLACEHOLDER: &str = "{file}";
+
+pub struct FileHeaderOptions<'a> {
+  pub template: &'a str,
+  pub mode: FileHeaderMode,
+  pub file_name: &'a str,
+  /// The year for new headers, or `None` when it isn't known.
+  pub year: Option<u32>,
+}
+
+/// Inserts, replaces or removes the header comment after the opening tag.
+///
+/// An existing header matches the template when only its whitespace, comment
+/// markers or year differ. A matching header keeps its year so that the
+/// headers don't change every new year.
+pub fn transform(text: &[u8], options: &FileHeaderOptions) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let header = file_header(program)?;
+  // a comment directly above the first statement is only the header when it
+  // matches the template
+  let block = match first_block(text, &header.comments) {
+    block if block.is_empty() => first_block(text, &header.attached),
+    block => block,
+  };
+  let template = options.template.trim().replace(FILE_PLACEHOLDER, options.file_name);
+
+  let existing = match (block.first(), block.last()) {
+    (Some(first), Some(last)) => Some(first.span.start.offset..last.span.end.offset),
+    _ => None,
+  };
+  let matched_year = existing
+    .as_ref()
+    .and_then(|range| match_template(&template, &text[range.start as usize..range.end as usize]));
+  let existing = existing.filter(|_| matched_year.is_some() || !header.comments.is_empty());
+
+  let new_year = options.year.map(|year| year.to_string());
+  let edit = match (options.mode, existing, matched_year) {
+    (FileHeaderMode::Remove, Some(range), Some(_)) => TextEdit::delete(range),
+    (FileHeaderMode::Remove, _, _) => return None,
+    (_, Some(range), Some(year)) => TextEdit::replace(range, render(&template, year.or(new_year).as_deref())?),
+    (FileHeaderMode::Replace, Some(range), None) => TextEdit::replace(range, render(&template, new_year.as_deref())?),
+    (FileHeaderMode::Insert | FileHeaderMode::Replace, _, _) => {
+      let comment = render(&template, new_year.as_deref())?;
+      TextEdit::insert(header.tag_end, format!("\n\n{comment}\n\n"))
+    }
+  };
+
+  let mut editor = TextEditor::new(text);
+  editor.apply(edit, None::<fn(&[u8]) -> bool>);
+  let result = editor.finish();
+  if result == text { None } else { Some(result) }
+}
+
+/// Gets the leading comments of the header that aren't separated by a blank
+/// line, such as a block comment or a run of line comments.
+fn first_block<'ast, 'arena>(text: &[u8], comments: &[&'ast Trivia<'arena>]) -> Vec<&'ast Trivia<'arena>> {
+  let mut block = Vec::new();
+  for comment in comments {
+    if let Some(previous) = block.last().map(|previous: &&Trivia| previous.span.end.offset) {
+      let gap = &text[previous as usize..comment.span.start.offset as usize];
+      if gap.iter().filter(|byte| **byte == b'\n').count() > 1 {
+        break;
+      }
+    }
+    block.push(*comment);
+  }
+  block
+}
+
+/// Renders the template as a block comment, unless it's already a comment.
+fn render(template: &str, year: Option<&str>) -> Option<String> {
+  let template = if template.contains(YEAR_PLACEHOLDER) {
+    template.replace(YEAR_PLACEHOLDER, year?)
+  } else {
+    template.to_string()
+  };
+  if is_comment(&template) {
+    return Some(template);
+  }
+
+  let mut result = String::from("/*");
+  for line in template.lines() {
+    let line = line.trim_end();
+    result.push_str("\n *");
+    if !line.is_empty() {
+      result.push(' ');
+      result.push_str(line);
+    }
+  }
+  result.push_str("\n */");
+  Some(result)
+}
+
+fn is_comment(text: &str) -> bool {
+  text.starts_with("/*") || text.starts_with("//") || text.starts_with('#')
+}
+
+/// Matches the words of an existing header against the template, returning
+/// the year it contains when the template has a year placeholder.
+fn match_template(template: &str, existing: &[u8]) -> Option<Option<String>> {
+  let existing = std::str::from_utf8(existing).ok()?;
+  let template_words = if is_comment(template) {
+    comment_words(template)
+  } else {
+    template.split_whitespace().collect()
+  };
+  let existing_words = comment_words(existing);
+  if template_words.len() != existing_words.len() {
+    return None;
+  }
+
+  let mut year = None;
+  for (template_word, existing_word) in template_words.iter().zip(existing_words) {
+    match template_word.split_once(YEAR_PLACEHOLDER) {
+      Some((prefix, suffix)) => {
+        let value = existing_word.strip_prefix(prefix)?.strip_suffix(suffix)?;
+        if !is_year(value) {
+          return None;
+        }
+        year.get_or_insert_with(|| value.to_string());
+      }
+      None if *template_word == existing_word => {}
+      None => return None,
+    }
+  }
+  Some(year)
+}
+
+/// Gets the words of a comment without its comment markers.
+fn comment_words(comment: &str) -> Vec<&str> {
+  let content = comment
+    .strip_prefix("/*")
+    .map(|content| content.strip_suffix("*/").unwrap_or(content));
+  match content {
+    Some(content) => content
+      .lines()
+      .flat_map(|line| line.trim_start().trim_start_matches('*').split_whitespace())
+      .collect(),
+    None => comment
+      .lines()
+      .flat_map(|line| {
+        let line = line.trim_start();
+        let line = line
+          .strip_prefix("//")
+          .or_else(|| line.strip_prefix('#'))
+          .unwrap_or(line);
+        line.split_whitespace()
+      })
+      .collect(),
+  }
+}
+
+/// Gets whether the text is a year or a range of years (ex. `2019-2024`).
+fn is_year(text: &str) -> bool {
+  let is_single = |text: &str| text.len() == 4 && text.bytes().all(|byte| byte.is_ascii_digit());
+  match text.split_once('-') {
+    Some((start, end)) => is_single(start) && is_single(end),
+    None => is_single(text),
+  }
+}
+
+/// Gets the current year from the system clock, which isn't available when
+/// running as a Wasm plugin.
+pub fn current_year() -> Option<u32> {
+  if cfg!(target_arch = "wasm32") {
+    return None;
+  }
+
+  let days = std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .ok()?
+    .as_secs()
+    / 86_400;
+  // converts days since the epoch to a civil year (Howard Hinnant's algorithm)
+  let days = days as i64 + 719_468;
+  let era = days.div_euclid(146_097);
+  let day_of_era = days - era * 146_097;
+  let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let year = year_of_era + era * 400 + if month_index >= 10 { 1 } else { 0 };
+  Some(year as u32)
+}
@@ -0,0 +1,81 @@
+use mago_allocator::LocalArena;
+use mago_syntax::cst::DocumentKind;
+use mago_syntax::cst::Node;
+use mago_syntax::cst::StringPart;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use super::parse;
+
+/// Turns heredocs into nowdocs when that doesn't change their value.
+///
+/// A heredoc is left alone when it interpolates anything or contains an
+/// escape sequence, since a nowdoc would print those as written.
+pub fn transform(text: &[u8]) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut editor = TextEditor::new(text);
+
+  for document in Node::Program(program).filter_map(|node| match node {
+    Node::DocumentString(document) if document.kind == DocumentKind::Heredoc => Some(*document),
+    _ => None,
+  }) {
+    let is_literal = document.parts.iter().all(|part| match part {
+      StringPart::Literal(literal) => !has_escape_sequence(literal.raw),
+      _ => false,
+    });
+    if !is_literal {
+      continue;
+    }
+
+    let start = document.open.start.offset as usize;
+    let open = &text[start..document.open.end.offset as usize];
+    let Some(label_start) = open
+      .windows(document.label.len())
+      .position(|window| window == document.label)
+    else {
+      continue;
+    };
+    let label_end = label_start + document.label.len();
+    // the label of a heredoc may be double quoted
+    let quote_start = if open[..label_start].ends_with(b"\"") {
+      label_start - 1
+    } else {
+      label_start
+    };
+    let quote_end = if open[label_end..].starts_with(b"\"") {
+      label_end + 1
+    } else {
+      label_end
+    };
+
+    let mut nowdoc_label = Vec::with_capacity(document.label.len() + 2);
+    nowdoc_label.push(b'\'');
+    nowdoc_label.extend_from_slice(document.label);
+    nowdoc_label.push(b'\'');
+    editor.apply(
+      TextEdit::replace((start + quote_start) as u32..(start + quote_end) as u32, nowdoc_label),
+      None::<fn(&[u8]) -> bool>,
+    );
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
+
+/// Gets whether the raw text of a heredoc has a backslash sequence that a
+/// heredoc unescapes, unlike `\"` or `\d` which it keeps as written.
+fn has_escape_sequence(raw: &[u8]) -> bool {
+  raw.windows(2).enumerate().any(|(index, pair)| {
+    pair[0] == b'\\'
+      && match pair[1] {
+        b'n' | b't' | b'r' | b'v' | b'e' | b'f' | b'\\' | b'$' | b'0'..=b'7' => true,
+        b'x' => raw.get(index + 2).is_some_and(|byte| byte.is_ascii_hexdigit()),
+        b'u' => raw.get(index + 2) == Some(&b'{'),
+        _ => false,
+      }
+  })
+}
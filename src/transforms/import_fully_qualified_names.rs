@@ -0,0 +1,323 @@
+use std::collections::HashSet;
+
+use mago_allocator::LocalArena;
+use mago_span::HasSpan;
+use mago_span::Span;
+use mago_syntax::cst::Expression;
+use mago_syntax::cst::Identifier;
+use mago_syntax::cst::InlineKind;
+use mago_syntax::cst::NamespaceBody;
+use mago_syntax::cst::Node;
+use mago_syntax::cst::Program;
+use mago_syntax::cst::Statement;
+use mago_syntax::cst::UseItem;
+use mago_syntax::cst::UseItems;
+use mago_syntax::cst::UseType;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use super::file_header;
+use super::import_scope;
+use super::parse;
+use super::remove_unused_uses::collect_docblock_references;
+
+/// The kinds of fully qualified names to import.
+#[derive(Clone, Copy)]
+pub struct ImportKinds {
+  pub classes: bool,
+  pub functions: bool,
+  pub constants: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+  Class,
+  Function,
+  Constant,
+}
+
+struct Import<'a> {
+  kind: Kind,
+  alias: &'a [u8],
+  name: &'a [u8],
+}
+
+/// Replaces fully qualified references in code with their short name and
+/// imports them with a `use` statement.
+///
+/// A reference is skipped when its short name is already imported for a
+/// different name, declared in the file or used unqualified elsewhere,
+/// including in docblocks. In the global namespace, names without a
+/// namespace are left alone since they're already short.
+pub fn transform(text: &[u8], kinds: ImportKinds) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let (namespace, statements) = import_scope(program)?;
+  let namespace_name = match namespace {
+    Some(namespace) => namespace.name.as_ref()?.value(),
+    None => b"",
+  };
+
+  let mut imports = statements
+    .iter()
+    .filter_map(|statement| match statement {
+      Statement::Use(r#use) => Some(&r#use.items),
+      _ => None,
+    })
+    .flat_map(existing_imports)
+    .collect::<Vec<_>>();
+
+  let mut declared = HashSet::new();
+  let mut unqualified = HashSet::new();
+  let mut references = Vec::new();
+  collect_declarations(statements, &mut declared);
+  collect_references(Node::Program(program), &mut unqualified, &mut references);
+  collect_docblock_references(program, &mut unqualified);
+
+  let mut editor = TextEditor::new(text);
+  let mut new_imports = Vec::new();
+  for (kind, span, identifier) in references {
+    let enabled = match kind {
+      Kind::Class => kinds.classes,
+      Kind::Function => kinds.functions,
+      Kind::Constant => kinds.constants,
+    };
+    let name = &identifier.value()[1..];
+    let alias = identifier.last_segment();
+    if !enabled || (kind == Kind::Constant && is_special_constant(name)) || (namespace.is_none() && name == alias) {
+      continue;
+    }
+
+    match imports
+      .iter()
+      .find(|import| import.kind == kind && names_equal(kind, import.alias, alias))
+    {
+      Some(import) if !names_equal(kind, import.name, name) => continue,
+      Some(_) => {}
+      None => {
+        let in_namespace =
+          name.len() > alias.len() && names_equal(Kind::Class, &name[..name.len() - alias.len() - 1], namespace_name);
+        if !in_namespace {
+          if declared.contains(&(kind, normalize(kind, alias))) || unqualified.contains(&alias.to_ascii_lowercase()) {
+            continue;
+          }
+          imports.push(Import { kind, alias, name });
+          new_imports.push((kind, name));
+        }
+      }
+    }
+
+    editor.apply(
+      TextEdit::replace(span.start.offset..span.end.offset, alias),
+      None::<fn(&[u8]) -> bool>,
+    );
+  }
+
+  if editor.get_edits().is_empty() {
+    return None;
+  }
+
+  if !new_imports.is_empty() {
+    let mut use_text = Vec::new();
+    for (kind, name) in new_imports {
+      use_text.extend_from_slice(match kind {
+        Kind::Class => b"\nuse ".as_slice(),
+        Kind::Function => b"\nuse function ".as_slice(),
+        Kind::Constant => b"\nuse const ".as_slice(),
+      });
+      use_text.extend_from_slice(name);
+      use_text.push(b';');
+    }
+
+    // add the imports after the existing ones and let the formatter sort them
+    let last_use = statements
+      .iter()
+      .rfind(|statement| matches!(statement, Statement::Use(_)));
+    let offset = match (last_use, namespace.map(|namespace| &namespace.body)) {
+      (Some(last_use), _) => last_use.span().end.offset,
+      (None, Some(NamespaceBody::Implicit(body))) => {
+        use_text.insert(0, b'\n');
+        body.terminator.span().end.offset
+      }
+      (None, Some(NamespaceBody::BraceDelimited(block))) => block.left_brace.end.offset,
+      (None, None) => {
+        use_text.insert(0, b'\n');
+        global_imports_offset(program)?
+      }
+    };
+    editor.apply(TextEdit::insert(offset, use_text), None::<fn(&[u8]) -> bool>);
+  }
+
+  Some(editor.finish())
+}
+
+/// Gets where imports go in a file without a namespace or imports, which is
+/// after its `declare` statements or else after its header comment.
+fn global_imports_offset(program: &Program) -> Option<u32> {
+  let last_declare = program
+    .statements
+    .iter()
+    .take_while(|statement| {
+      matches!(statement, Statement::OpeningTag(_) | Statement::Declare(_))
+        || matches!(statement, Statement::Inline(inline) if inline.kind == InlineKind::Shebang)
+    })
+    .filter(|statement| matches!(statement, Statement::Declare(_)))
+    .last();
+  if let Some(declare) = last_declare {
+    return Some(declare.span().end.offset);
+  }
+
+  let header = file_header(program)?;
+  Some(
+    header
+      .comments
+      .last()
+      .map(|comment| comment.span.end.offset)
+      .unwrap_or(header.tag_end),
+  )
+}
+
+fn existing_imports<'a>(items: &'a UseItems<'a>) -> Vec<Import<'a>> {
+  let import = |kind: Kind, item: &UseItem<'a>| Import {
+    kind,
+    alias: match &item.alias {
+      Some(alias) => alias.identifier.value,
+      None => item.name.last_segment(),
+    },
+    name: item.name.value().strip_prefix(b"\\").unwrap_or(item.name.value()),
+  };
+  let kind_of = |r#type: Option<&UseType>| match r#type {
+    Some(r#type) if r#type.is_function() => Kind::Function,
+    Some(r#type) if r#type.is_const() => Kind::Constant,
+    _ => Kind::Class,
+  };
+
+  // names in group lists are relative to the group prefix, so they get an
+  // empty name that never matches and their aliases only count as collisions
+  match items {
+    UseItems::Sequence(sequence) => sequence.items.iter().map(|item| import(Kind::Class, item)).collect(),
+    UseItems::TypedSequence(sequence) => {
+      let kind = kind_of(Some(&sequence.r#type));
+      sequence.items.iter().map(|item| import(kind, item)).collect()
+    }
+    UseItems::TypedList(list) => {
+      let kind = kind_of(Some(&list.r#type));
+      list
+        .items
+        .iter()
+        .map(|item| Import {
+          name: b"",
+          ..import(kind, item)
+        })
+        .collect()
+    }
+    UseItems::MixedList(list) => list
+      .items
+      .iter()
+      .map(|item| Import {
+        name: b"",
+        ..import(kind_of(item.r#type.as_ref()), &item.item)
+      })
+      .collect(),
+  }
+}
+
+fn collect_declarations(statements: &[Statement], declared: &mut HashSet<(Kind, Vec<u8>)>) {
+  for statement in statements {
+    let (kind, name) = match statement {
+      Statement::Class(class) => (Kind::Class, class.name.value),
+      Statement::Interface(interface) => (Kind::Class, interface.name.value),
+      Statement::Trait(r#trait) => (Kind::Class, r#trait.name.value),
+      Statement::Enum(r#enum) => (Kind::Class, r#enum.name.value),
+      Statement::Function(function) => (Kind::Function, function.name.value),
+      Statement::Constant(constant) => {
+        for item in constant.items.iter() {
+          declared.insert((Kind::Constant, item.name.value.to_vec()));
+        }
+        continue;
+      }
+      _ => continue,
+    };
+    declared.insert((kind, normalize(kind, name)));
+  }
+}
+
+/// Collects the fully qualified references whose kind is known from where
+/// they appear, along with the first segment of every other name.
+fn collect_references<'ast, 'arena>(
+  node: Node<'ast, 'arena>,
+  unqualified: &mut HashSet<Vec<u8>>,
+  references: &mut Vec<(Kind, Span, &'ast Identifier<'arena>)>,
+) {
+  let mut add_expression = |kind: Kind, expression: &'ast Expression<'arena>| {
+    if let Expression::Identifier(identifier) = expression {
+      add(kind, identifier, unqualified, references);
+    }
+  };
+
+  match node {
+    Node::Use(_) => return,
+    // the kind of a name used as an expression depends on its parent, so
+    // it's added by the cases below
+    Node::Expression(Expression::Identifier(identifier)) => {
+      if !identifier.is_fully_qualified() {
+        add(Kind::Class, identifier, unqualified, references);
+      }
+      return;
+    }
+    Node::ConstantAccess(access) => {
+      add(Kind::Constant, &access.name, unqualified, references);
+      return;
+    }
+    Node::FunctionCall(call) => add_expression(Kind::Function, call.function),
+    Node::Instantiation(instantiation) => add_expression(Kind::Class, instantiation.class),
+    Node::StaticMethodCall(call) => add_expression(Kind::Class, call.class),
+    Node::StaticPropertyAccess(access) => add_expression(Kind::Class, access.class),
+    Node::ClassConstantAccess(access) => add_expression(Kind::Class, access.class),
+    Node::Binary(binary) if binary.operator.is_instanceof() => add_expression(Kind::Class, binary.rhs),
+    // names outside of expressions are types, attributes, parents and traits
+    Node::Identifier(identifier) => add(Kind::Class, identifier, unqualified, references),
+    _ => {}
+  }
+
+  node.visit_children(|child| collect_references(child, unqualified, references));
+}
+
+fn add<'ast, 'arena>(
+  kind: Kind,
+  identifier: &'ast Identifier<'arena>,
+  unqualified: &mut HashSet<Vec<u8>>,
+  references: &mut Vec<(Kind, Span, &'ast Identifier<'arena>)>,
+) {
+  if identifier.is_fully_qualified() {
+    references.push((kind, identifier.span(), identifier));
+  } else {
+    let first_segment = identifier
+      .value()
+      .split(|byte| *byte == b'\\')
+      .next()
+      .unwrap_or_default();
+    unqualified.insert(first_segment.to_ascii_lowercase());
+  }
+}
+
+/// Class and function names are case-insensitive while constants are not.
+fn normalize(kind: Kind, name: &[u8]) -> Vec<u8> {
+  match kind {
+    Kind::Constant => name.to_vec(),
+    Kind::Class | Kind::Function => name.to_ascii_lowercase(),
+  }
+}
+
+fn names_equal(kind: Kind, a: &[u8], b: &[u8]) -> bool {
+  match kind {
+    Kind::Constant => a == b,
+    Kind::Class | Kind::Function => a.eq_ignore_ascii_case(b),
+  }
+}
+
+fn is_special_constant(name: &[u8]) -> bool {
+  [b"true".as_slice(), b"false", b"null"]
+    .iter()
+    .any(|constant| name.eq_ignore_ascii_case(constant))
+}
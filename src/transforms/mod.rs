@@ -0,0 +1,381 @@
+//! Source rewrites applied around the mago formatter.
+//!
+//! Input transforms run before the text is handed to mago and output
+//! transforms run on what it printed. Each one parses the current text,
+//! computes byte edits and returns the rewritten text, or `None` when there
+//! was nothing to change. Files with syntax errors or a mago format-ignore
+//! marker are left alone so the formatter can report or skip them as usual.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use mago_allocator::LocalArena;
+use mago_database::file::FileId;
+use mago_php_version::feature::Feature;
+use mago_span::HasSpan;
+use mago_syntax::cst::InlineKind;
+use mago_syntax::cst::Namespace;
+use mago_syntax::cst::OpeningTag;
+use mago_syntax::cst::Program;
+use mago_syntax::cst::Statement;
+use mago_syntax::cst::Trivia;
+use mago_syntax::cst::TriviaKind;
+use mago_syntax::parser::parse_file_content;
+
+use crate::configuration::AlternativeSyntax;
+use crate::configuration::ArraySyntax;
+use crate::configuration::Configuration;
+use crate::configuration::EchoTagStyle;
+use crate::configuration::ElseIfStyle;
+use crate::configuration::FileHeaderMode;
+use crate::configuration::HeredocStyle;
+use crate::configuration::LetterCase;
+use crate::configuration::SortOrder;
+use crate::format_text::php_version;
+
+mod alternative_syntax;
+mod array_syntax;
+mod casing;
+mod comments;
+mod docblocks;
+mod else_if_style;
+mod explicit_visibility;
+mod file_header;
+mod heredoc_style;
+mod import_fully_qualified_names;
+mod normalize_casts;
+mod numeric_literals;
+mod open_tags;
+mod ordered_class_elements;
+mod remove_unused_uses;
+mod require_control_braces;
+mod require_strict_types;
+mod sort_enum_cases;
+mod sort_name_lists;
+mod wrap_comments;
+
+const FORMAT_IGNORE_MARKERS: [&[u8]; 2] = [b"@mago-format-ignore", b"@mago-formatter-ignore"];
+
+/// Applies the enabled transforms to the input, returning the rewritten text
+/// or `None` when no transform changed it.
+pub(crate) fn transform_input(file_path: &Path, input: &[u8], config: &Configuration) -> Option<Vec<u8>> {
+  if has_format_ignore_marker(input) {
+    return None;
+  }
+
+  let mut text = Cow::Borrowed(input);
+
+  let replace_short_open_tags = config.replace_short_open_tags == Some(true);
+  let echo_tag_style = config.echo_tag_style.unwrap_or(EchoTagStyle::Preserve);
+  if replace_short_open_tags || echo_tag_style != EchoTagStyle::Preserve {
+    apply(&mut text, |text| {
+      open_tags::transform(text, replace_short_open_tags, echo_tag_style)
+    });
+  }
+
+  let comments = comments::CommentOptions {
+    space_after_line_comment: config.space_after_line_comment == Some(true),
+    convert_single_line_block_comments: config.convert_single_line_block_comments == Some(true),
+  };
+  if comments.space_after_line_comment || comments.convert_single_line_block_comments {
+    apply(&mut text, |text| comments::transform(text, comments));
+  }
+
+  let casing = casing::CasingOptions {
+    lowercase_keywords: config.lowercase_keywords == Some(true),
+    lowercase_native_types: config.lowercase_native_types == Some(true),
+    uppercase_magic_constants: config.uppercase_magic_constants == Some(true),
+  };
+  if casing.lowercase_keywords || casing.lowercase_native_types || casing.uppercase_magic_constants {
+    apply(&mut text, |text| casing::transform(text, casing));
+  }
+
+  if config.normalize_casts == Some(true) {
+    let php_version = php_version(config);
+    apply(&mut text, |text| normalize_casts::transform(text, php_version));
+  }
+
+  if config.heredoc_style == Some(HeredocStyle::PreferNowdoc) {
+    apply(&mut text, heredoc_style::transform);
+  }
+
+  let numeric_literals = numeric_literals::NumericLiteralOptions {
+    hex_digit_case: config.numeric_literal_hex_digit_case.unwrap_or(LetterCase::Preserve),
+    prefix_case: config.numeric_literal_prefix_case.unwrap_or(LetterCase::Preserve),
+    exponent_case: config.numeric_literal_exponent_case.unwrap_or(LetterCase::Preserve),
+    // digit separators need PHP 7.4
+    separator_threshold: config
+      .numeric_literal_separator_threshold
+      .filter(|_| php_version(config).is_supported(Feature::NumericLiteralSeparator))
+      .map(usize::from),
+  };
+  if numeric_literals.hex_digit_case != LetterCase::Preserve
+    || numeric_literals.prefix_case != LetterCase::Preserve
+    || numeric_literals.exponent_case != LetterCase::Preserve
+    || numeric_literals.separator_threshold.is_some()
+  {
+    apply(&mut text, |text| numeric_literals::transform(text, numeric_literals));
+  }
+
+  if let Some(template) = config.file_header.as_deref() {
+    let options = file_header::FileHeaderOptions {
+      template,
+      mode: config.file_header_mode.unwrap_or(FileHeaderMode::Insert),
+      file_name: &file_path.file_name().unwrap_or_default().to_string_lossy(),
+      year: config
+        .file_header_year
+        .map(u32::from)
+        .or_else(file_header::current_year),
+    };
+    apply(&mut text, |text| file_header::transform(text, &options));
+  }
+
+  if config.require_strict_types == Some(true) {
+    apply(&mut text, require_strict_types::transform);
+  }
+
+  if config.require_control_braces == Some(true) {
+    apply(&mut text, require_control_braces::transform);
+  }
+
+  let else_if_style = config.else_if_style.unwrap_or(ElseIfStyle::Preserve);
+  if else_if_style != ElseIfStyle::Preserve {
+    apply(&mut text, |text| else_if_style::transform(text, else_if_style));
+  }
+
+  let alternative_syntax = config.alternative_syntax.unwrap_or(AlternativeSyntax::Preserve);
+  if alternative_syntax != AlternativeSyntax::Preserve {
+    apply(&mut text, |text| {
+      alternative_syntax::transform(text, alternative_syntax)
+    });
+  }
+
+  if config.explicit_visibility == Some(true) {
+    let kinds = explicit_visibility::VisibilityKinds {
+      properties: config.explicit_visibility_properties.unwrap_or(true),
+      methods: config.explicit_visibility_methods.unwrap_or(true),
+      // constant visibility needs PHP 7.1
+      constants: config.explicit_visibility_constants.unwrap_or(true)
+        && php_version(config).is_supported(Feature::ClassLikeConstantVisibilityModifiers),
+    };
+    apply(&mut text, |text| explicit_visibility::transform(text, kinds));
+  }
+
+  let array_syntax = config.array_syntax.unwrap_or(ArraySyntax::Preserve);
+  let mut list_syntax = config.list_syntax.unwrap_or(ArraySyntax::Preserve);
+  // short destructuring needs PHP 7.1
+  if list_syntax == ArraySyntax::Short && !php_version(config).is_at_least(7, 1, 0) {
+    list_syntax = ArraySyntax::Preserve;
+  }
+  if array_syntax != ArraySyntax::Preserve || list_syntax != ArraySyntax::Preserve {
+    apply(&mut text, |text| {
+      array_syntax::transform(text, array_syntax, list_syntax)
+    });
+  }
+
+  if config.import_fully_qualified_names == Some(true) {
+    let kinds = import_fully_qualified_names::ImportKinds {
+      classes: config.import_fully_qualified_classes.unwrap_or(true),
+      functions: config.import_fully_qualified_functions.unwrap_or(false),
+      constants: config.import_fully_qualified_constants.unwrap_or(false),
+    };
+    apply(&mut text, |text| import_fully_qualified_names::transform(text, kinds));
+  }
+
+  if config.remove_unused_uses == Some(true) {
+    apply(&mut text, remove_unused_uses::transform);
+  }
+
+  if let Some(groups) = config.ordered_class_elements.as_deref()
+    && !groups.is_empty()
+  {
+    let sort_order = config.ordered_class_elements_sort_order.unwrap_or(SortOrder::Preserve);
+    apply(&mut text, |text| {
+      ordered_class_elements::transform(text, groups, sort_order)
+    });
+  }
+
+  let implements_order = config.sort_implements.unwrap_or(SortOrder::Preserve);
+  let trait_use_order = config.sort_trait_uses.unwrap_or(SortOrder::Preserve);
+  if implements_order != SortOrder::Preserve || trait_use_order != SortOrder::Preserve {
+    apply(&mut text, |text| {
+      sort_name_lists::transform(text, implements_order, trait_use_order)
+    });
+  }
+
+  if let Some(sort_order) = config.sort_enum_cases
+    && sort_order != SortOrder::Preserve
+  {
+    apply(&mut text, |text| sort_enum_cases::transform(text, sort_order));
+  }
+
+  match text {
+    Cow::Owned(text) => Some(text),
+    Cow::Borrowed(_) => None,
+  }
+}
+
+/// Applies the enabled transforms to the formatted output, returning the
+/// rewritten text or `None` when no transform changed it.
+pub(crate) fn transform_output(output: &[u8], config: &Configuration) -> Option<Vec<u8>> {
+  if has_format_ignore_marker(output) {
+    return None;
+  }
+
+  let mut text = Cow::Borrowed(output);
+
+  if config.format_docblocks == Some(true) {
+    let options = docblocks::DocblockOptions {
+      print_width: config.print_width.unwrap_or(120) as usize,
+      tab_width: config.tab_width.unwrap_or(4) as usize,
+      tag_order: config.docblock_tag_order.as_deref().unwrap_or_default(),
+    };
+    apply(&mut text, |text| docblocks::transform(text, &options));
+  }
+
+  if config.wrap_comments == Some(true) {
+    let options = wrap_comments::WrapOptions {
+      print_width: config.print_width.unwrap_or(120) as usize,
+      tab_width: config.tab_width.unwrap_or(4) as usize,
+    };
+    apply(&mut text, |text| wrap_comments::transform(text, &options));
+  }
+
+  match text {
+    Cow::Owned(text) => Some(text),
+    Cow::Borrowed(_) => None,
+  }
+}
+
+fn apply(text: &mut Cow<[u8]>, transform: impl FnOnce(&[u8]) -> Option<Vec<u8>>) {
+  if let Some(new_text) = transform(text) {
+    *text = Cow::Owned(new_text);
+  }
+}
+
+/// Parses the text, returning `None` when it has syntax errors.
+fn parse<'arena>(arena: &'arena LocalArena, text: &[u8]) -> Option<&'arena Program<'arena>> {
+  let program = parse_file_content(arena, FileId::new(b"file.php"), text);
+  if program.errors.is_empty() { Some(program) } else { None }
+}
+
+/// Gets the end of the comments on the same line after the offset.
+fn trailing_comments_end(program: &Program, offset: u32) -> u32 {
+  let text = program.source_text;
+  let mut end = offset;
+  for comment in program
+    .trivia
+    .iter()
+    .filter(|trivia| trivia.kind.is_comment() && trivia.span.start.offset >= offset)
+  {
+    let gap = &text[end as usize..comment.span.start.offset as usize];
+    if !gap.iter().all(|byte| matches!(byte, b' ' | b'\t')) {
+      break;
+    }
+    end = comment.span.end.offset;
+  }
+  end
+}
+
+/// Any ignore marker, including the `-start`/`-next` variants, disables the
+/// transforms for the whole file since they can't tell which regions are
+/// ignored by the formatter.
+fn has_format_ignore_marker(text: &[u8]) -> bool {
+  FORMAT_IGNORE_MARKERS
+    .iter()
+    .any(|marker| text.windows(marker.len()).any(|window| window == *marker))
+}
+
+/// Gets the namespace of the file and the statements its imports are declared
+/// in, or `None` when there are several namespaces since imports are scoped
+/// to each of them.
+fn import_scope<'ast, 'arena>(
+  program: &'ast Program<'arena>,
+) -> Option<(Option<&'ast Namespace<'arena>>, &'ast [Statement<'arena>])> {
+  let mut namespaces = program.statements.iter().filter_map(|statement| match statement {
+    Statement::Namespace(namespace) => Some(namespace),
+    _ => None,
+  });
+
+  match (namespaces.next(), namespaces.next()) {
+    (None, _) => Some((None, program.statements.as_slice())),
+    (Some(namespace), None) => Some((Some(namespace), namespace.statements().as_slice())),
+    (Some(_), Some(_)) => None,
+  }
+}
+
+struct FileHeader<'ast, 'arena> {
+  /// The end of the opening tag.
+  tag_end: u32,
+  /// The comments on the opening tag line and those followed by a blank
+  /// line, along with the comments directly above the first statement when
+  /// they can't be documenting it.
+  comments: Vec<&'ast Trivia<'arena>>,
+  /// The comments directly above the first statement that belong to it.
+  attached: Vec<&'ast Trivia<'arena>>,
+}
+
+/// Gets the header of a file that starts with a full opening tag after an
+/// optional shebang, or `None` when code can't be added before its first
+/// statement.
+fn file_header<'ast, 'arena>(program: &'ast Program<'arena>) -> Option<FileHeader<'ast, 'arena>> {
+  let text = program.source_text;
+  let mut statements =
+    program.statements.iter().enumerate().skip_while(
+      |(_, statement)| matches!(statement, Statement::Inline(inline) if inline.kind == InlineKind::Shebang),
+    );
+  let (index, Statement::OpeningTag(OpeningTag::Full(tag))) = statements.next()? else {
+    return None;
+  };
+  let tag_end = tag.span.end.offset;
+  let next_statement = program.statements.get(index + 1);
+  let next_start = next_statement.map(|statement| statement.span().start.offset);
+
+  let mut comments = program
+    .trivia
+    .iter()
+    .filter(|trivia| trivia.kind.is_comment())
+    .filter(|trivia| trivia.span.start.offset >= tag_end)
+    .take_while(|trivia| next_start.is_none_or(|start| trivia.span.end.offset <= start))
+    .collect::<Vec<_>>();
+  // without any statements, every comment is part of the header
+  let Some(next_start) = next_start else {
+    return Some(FileHeader {
+      tag_end,
+      comments,
+      attached: Vec::new(),
+    });
+  };
+
+  let mut header_length = 0;
+  for (index, comment) in comments.iter().enumerate() {
+    let following_start = comments
+      .get(index + 1)
+      .map(|comment| comment.span.start.offset)
+      .unwrap_or(next_start);
+    let preceding = &text[tag_end as usize..comment.span.start.offset as usize];
+    let following = &text[comment.span.end.offset as usize..following_start as usize];
+    let on_tag_line = index == 0 && !preceding.contains(&b'\n');
+    let before_blank_line = following.iter().filter(|byte| **byte == b'\n').count() > 1;
+    if on_tag_line || before_blank_line {
+      header_length = index + 1;
+    }
+  }
+  let mut attached = comments.split_off(header_length);
+  // namespaces and imports aren't documented by the comments above them, and
+  // `/* */` comments are licence blocks rather than docblocks
+  let is_header = matches!(
+    next_statement,
+    Some(Statement::Namespace(_) | Statement::Use(_) | Statement::Declare(_))
+  ) || attached
+    .iter()
+    .all(|comment| comment.kind == TriviaKind::MultiLineComment);
+  if is_header {
+    comments.append(&mut attached);
+  }
+  Some(FileHeader {
+    tag_end,
+    comments,
+    attached,
+  })
+}
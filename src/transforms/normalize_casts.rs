@@ -0,0 +1,53 @@
+use mago_allocator::LocalArena;
+use mago_php_version::PHPVersion;
+use mago_php_version::feature::Feature;
+use mago_span::HasSpan;
+use mago_syntax::cst::Node;
+use mago_syntax::cst::UnaryPrefixOperator;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use super::parse;
+
+/// Rewrites casts to their canonical lowercase spelling without inner spaces,
+/// such as `(integer)` and `( INT )` to `(int)`.
+///
+/// The `(unset)` cast has no replacement and is left alone, as are casts the
+/// configured PHP version doesn't support.
+pub fn transform(text: &[u8], php_version: PHPVersion) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut editor = TextEditor::new(text);
+
+  for (span, canonical) in Node::Program(program).filter_map(|node| {
+    let Node::UnaryPrefixOperator(operator) = node else {
+      return None;
+    };
+    let canonical = match operator {
+      UnaryPrefixOperator::ArrayCast(..) => "(array)",
+      UnaryPrefixOperator::BoolCast(..) | UnaryPrefixOperator::BooleanCast(..) => "(bool)",
+      UnaryPrefixOperator::DoubleCast(..) | UnaryPrefixOperator::RealCast(..) | UnaryPrefixOperator::FloatCast(..) => {
+        "(float)"
+      }
+      UnaryPrefixOperator::IntCast(..) | UnaryPrefixOperator::IntegerCast(..) => "(int)",
+      UnaryPrefixOperator::ObjectCast(..) => "(object)",
+      UnaryPrefixOperator::StringCast(..) | UnaryPrefixOperator::BinaryCast(..) => "(string)",
+      UnaryPrefixOperator::VoidCast(..) if php_version.is_supported(Feature::VoidCast) => "(void)",
+      _ => return None,
+    };
+    Some((operator.span(), canonical))
+  }) {
+    if &text[span.start.offset as usize..span.end.offset as usize] != canonical.as_bytes() {
+      editor.apply(
+        TextEdit::replace(span.start.offset..span.end.offset, canonical),
+        None::<fn(&[u8]) -> bool>,
+      );
+    }
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
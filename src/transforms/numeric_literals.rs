@@ -0,0 +1,109 @@
+use mago_allocator::LocalArena;
+use mago_syntax::cst::Literal;
+use mago_syntax::cst::Node;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use crate::configuration::LetterCase;
+
+use super::parse;
+
+#[derive(Clone, Copy)]
+pub struct NumericLiteralOptions {
+  pub hex_digit_case: LetterCase,
+  pub prefix_case: LetterCase,
+  pub exponent_case: LetterCase,
+  /// Decimal literals with more digits than this before the decimal point get
+  /// `_` separators between groups of three digits.
+  pub separator_threshold: Option<usize>,
+}
+
+/// Normalises the case of hex digits, base prefixes and exponents in numeric
+/// literals and inserts digit separators into long decimal literals.
+pub fn transform(text: &[u8], options: NumericLiteralOptions) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut editor = TextEditor::new(text);
+
+  for (span, raw) in Node::Program(program).filter_map(|node| match node {
+    Node::Literal(Literal::Integer(integer)) => Some((integer.span, integer.raw)),
+    Node::Literal(Literal::Float(float)) => Some((float.span, float.raw)),
+    _ => None,
+  }) {
+    let Ok(raw) = std::str::from_utf8(raw) else {
+      continue;
+    };
+    let normalized = normalize(raw, options);
+    if normalized != raw {
+      editor.apply(
+        TextEdit::replace(span.start.offset..span.end.offset, normalized),
+        None::<fn(&[u8]) -> bool>,
+      );
+    }
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
+
+fn normalize(raw: &str, options: NumericLiteralOptions) -> String {
+  let prefix = raw.get(..2).map(|prefix| prefix.to_ascii_lowercase());
+  match prefix.as_deref() {
+    Some("0x") => {
+      return format!(
+        "{}{}",
+        apply_case(&raw[..2], options.prefix_case),
+        apply_case(&raw[2..], options.hex_digit_case)
+      );
+    }
+    Some("0b" | "0o") => return format!("{}{}", apply_case(&raw[..2], options.prefix_case), &raw[2..]),
+    _ => {}
+  }
+
+  let raw = match raw.find(['e', 'E']) {
+    Some(index) => format!(
+      "{}{}{}",
+      &raw[..index],
+      apply_case(&raw[index..index + 1], options.exponent_case),
+      &raw[index + 1..]
+    ),
+    None => raw.to_string(),
+  };
+
+  match options.separator_threshold {
+    Some(threshold) => insert_separators(&raw, threshold),
+    None => raw,
+  }
+}
+
+fn apply_case(text: &str, case: LetterCase) -> String {
+  match case {
+    LetterCase::Lower => text.to_ascii_lowercase(),
+    LetterCase::Upper => text.to_ascii_uppercase(),
+    LetterCase::Preserve => text.to_string(),
+  }
+}
+
+/// Separates the digits before the decimal point into groups of three,
+/// leaving literals that already have separators as they are.
+fn insert_separators(raw: &str, threshold: usize) -> String {
+  let integer_end = raw.find(['.', 'e', 'E']).unwrap_or(raw.len());
+  let integer = &raw[..integer_end];
+  // a leading zero makes a legacy octal literal
+  if raw.contains('_') || integer.len() <= threshold || integer.starts_with('0') {
+    return raw.to_string();
+  }
+
+  let mut result = String::with_capacity(raw.len() + integer.len() / 3);
+  for (index, digit) in integer.chars().enumerate() {
+    if index > 0 && (integer.len() - index).is_multiple_of(3) {
+      result.push('_');
+    }
+    result.push(digit);
+  }
+  result.push_str(&raw[integer_end..]);
+  result
+}
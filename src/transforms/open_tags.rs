@@ -0,0 +1,105 @@
+use mago_allocator::LocalArena;
+use mago_span::HasSpan;
+use mago_syntax::cst::Echo;
+use mago_syntax::cst::EchoTag;
+use mago_syntax::cst::Node;
+use mago_syntax::cst::Terminator;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use crate::configuration::EchoTagStyle;
+
+use super::parse;
+
+/// Rewrites short open tags (`<?`) to `<?php` and converts single expression
+/// echo islands between `<?= $value ?>` and `<?php echo $value; ?>`.
+///
+/// Only the tags and the `echo` keyword change, so the inline HTML around
+/// them renders the same.
+pub fn transform(text: &[u8], replace_short_open_tags: bool, echo_tag_style: EchoTagStyle) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut editor = TextEditor::new(text);
+
+  for edit in Node::Program(program).filter_map(|node| match node {
+    Node::ShortOpeningTag(tag) if replace_short_open_tags => {
+      // `<?php` needs whitespace after it while `<?` doesn't (ex. `<?if`)
+      let followed_by_space = text
+        .get(tag.span.end.offset as usize)
+        .is_none_or(|byte| byte.is_ascii_whitespace());
+      let replacement = if followed_by_space { "<?php" } else { "<?php " };
+      Some(vec![TextEdit::replace(
+        tag.span.start.offset..tag.span.end.offset,
+        replacement,
+      )])
+    }
+    Node::Echo(echo) if echo_tag_style == EchoTagStyle::Short => echo_to_echo_tag(text, echo),
+    Node::EchoTag(echo_tag) if echo_tag_style == EchoTagStyle::Long => echo_tag_to_echo(text, echo_tag),
+    _ => None,
+  }) {
+    editor.apply_batch(edit, None::<fn(&[u8]) -> bool>);
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
+
+/// Converts `<?php echo $value; ?>` to `<?= $value ?>`.
+fn echo_to_echo_tag(text: &[u8], echo: &Echo) -> Option<Vec<TextEdit>> {
+  if echo.values.len() != 1 {
+    return None;
+  }
+
+  let echo_start = echo.echo.span.start.offset as usize;
+  // the opening tag must be on the same line, separated by spaces
+  let tag_end = text[..echo_start]
+    .iter()
+    .rposition(|byte| !matches!(byte, b' ' | b'\t'))
+    .map(|index| index + 1)?;
+  let tag_start = tag_end.checked_sub(5)?;
+  if tag_end == echo_start || !text[tag_start..tag_end].eq_ignore_ascii_case(b"<?php") {
+    return None;
+  }
+
+  let semicolon = closing_semicolon(text, &echo.terminator)?;
+  let mut edits = vec![TextEdit::replace(tag_start as u32..echo.echo.span.end.offset, "<?=")];
+  if let Some(semicolon) = semicolon {
+    edits.push(TextEdit::delete(semicolon..semicolon + 1));
+  }
+  Some(edits)
+}
+
+/// Converts `<?= $value ?>` to `<?php echo $value; ?>`.
+fn echo_tag_to_echo(text: &[u8], echo_tag: &EchoTag) -> Option<Vec<TextEdit>> {
+  if echo_tag.values.len() != 1 {
+    return None;
+  }
+
+  let semicolon = closing_semicolon(text, &echo_tag.terminator)?;
+  let mut edits = vec![TextEdit::replace(
+    echo_tag.tag.start.offset..echo_tag.tag.end.offset,
+    "<?php echo",
+  )];
+  if semicolon.is_none() {
+    let value_end = echo_tag.values.first()?.span().end.offset;
+    edits.push(TextEdit::insert(value_end, ";"));
+  }
+  Some(edits)
+}
+
+/// Gets the offset of the semicolon when the terminator closes the island on
+/// the same line, or `None` when it doesn't close it.
+fn closing_semicolon(text: &[u8], terminator: &Terminator) -> Option<Option<u32>> {
+  match terminator {
+    Terminator::ClosingTag(_) => Some(None),
+    Terminator::Semicolon(span) => {
+      let rest = &text[span.end.offset as usize..];
+      let spaces = rest.iter().take_while(|byte| matches!(byte, b' ' | b'\t')).count();
+      rest[spaces..].starts_with(b"?>").then_some(Some(span.start.offset))
+    }
+    _ => None,
+  }
+}
@@ -0,0 +1,332 @@
+use std::cmp::Ordering;
+
+use mago_allocator::LocalArena;
+use mago_span::HasSpan;
+use mago_span::Span;
+use mago_syntax::cst::ClassLikeMember;
+use mago_syntax::cst::Modifier;
+use mago_syntax::cst::Node;
+use mago_syntax::cst::Program;
+use mago_syntax::cst::Sequence;
+use mago_syntax::cst::TriviaKind;
+use mago_text_edit::ApplyResult;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use crate::configuration::ClassElementGroup;
+use crate::configuration::SortOrder;
+
+use super::parse;
+
+/// Reorders the members of every class-like in the text by the configured
+/// groups, moving each member together with its comments.
+pub fn transform(text: &[u8], groups: &[ClassElementGroup], sort_order: SortOrder) -> Option<Vec<u8>> {
+  let mut result: Option<Vec<u8>> = None;
+
+  // a class nested in a moved member (ex. an anonymous class in a method)
+  // overlaps the outer edit, so it's reordered on a following pass
+  loop {
+    let current = result.as_deref().unwrap_or(text);
+    let arena = LocalArena::new();
+    let program = parse(&arena, current)?;
+    let mut editor = TextEditor::new(current);
+    let mut deferred = false;
+
+    for body in class_like_bodies(program) {
+      let edits = reorder_edits(program, &body, groups, sort_order);
+      if !edits.is_empty() && editor.apply_batch(edits, None::<fn(&[u8]) -> bool>) == ApplyResult::Overlap {
+        deferred = true;
+      }
+    }
+
+    if editor.get_edits().is_empty() {
+      return result;
+    }
+
+    result = Some(editor.finish());
+
+    if !deferred {
+      return result;
+    }
+  }
+}
+
+pub(super) struct ClassLikeBody<'ast, 'arena> {
+  pub(super) left_brace: Span,
+  pub(super) members: &'ast Sequence<'arena, ClassLikeMember<'arena>>,
+  pub(super) right_brace: Span,
+}
+
+fn class_like_bodies<'ast, 'arena>(program: &'ast Program<'arena>) -> Vec<ClassLikeBody<'ast, 'arena>> {
+  Node::Program(program).filter_map(|node| match node {
+    Node::Class(class) => Some(ClassLikeBody {
+      left_brace: class.left_brace,
+      members: &class.members,
+      right_brace: class.right_brace,
+    }),
+    Node::Interface(interface) => Some(ClassLikeBody {
+      left_brace: interface.left_brace,
+      members: &interface.members,
+      right_brace: interface.right_brace,
+    }),
+    Node::Trait(r#trait) => Some(ClassLikeBody {
+      left_brace: r#trait.left_brace,
+      members: &r#trait.members,
+      right_brace: r#trait.right_brace,
+    }),
+    Node::Enum(r#enum) => Some(ClassLikeBody {
+      left_brace: r#enum.left_brace,
+      members: &r#enum.members,
+      right_brace: r#enum.right_brace,
+    }),
+    Node::AnonymousClass(class) => Some(ClassLikeBody {
+      left_brace: class.left_brace,
+      members: &class.members,
+      right_brace: class.right_brace,
+    }),
+    _ => None,
+  })
+}
+
+fn reorder_edits(
+  program: &Program,
+  body: &ClassLikeBody,
+  groups: &[ClassElementGroup],
+  sort_order: SortOrder,
+) -> Vec<TextEdit> {
+  let members = body.members.as_slice();
+  if members.len() < 2 {
+    return Vec::new();
+  }
+
+  let mut order = (0..members.len()).collect::<Vec<_>>();
+  let keys = members
+    .iter()
+    .map(|member| (group_index(member, groups), member_name(member)))
+    .collect::<Vec<_>>();
+  order.sort_by(|a, b| {
+    let (a_group, a_name) = &keys[*a];
+    let (b_group, b_name) = &keys[*b];
+    a_group
+      .cmp(b_group)
+      .then_with(|| compare_names(a_name, b_name, sort_order))
+  });
+
+  if order.iter().enumerate().all(|(slot, index)| slot == *index) {
+    return Vec::new();
+  }
+
+  let regions = member_regions(program, body);
+  order
+    .iter()
+    .enumerate()
+    .filter(|(slot, index)| slot != *index)
+    .map(|(slot, index)| {
+      let (start, end) = regions[slot];
+      TextEdit::replace(start..end, moved_region(program, &regions, slot, *index))
+    })
+    .collect()
+}
+
+/// Gets the byte range of each member, extended backwards over the comments
+/// on the lines before it and forwards over comments on its last line.
+pub(super) fn member_regions(program: &Program, body: &ClassLikeBody) -> Vec<(u32, u32)> {
+  let text = program.source_text;
+  let comments = program
+    .trivia
+    .iter()
+    .filter(|trivia| trivia.kind.is_comment())
+    .collect::<Vec<_>>();
+  let members = body.members.as_slice();
+  let mut regions = members
+    .iter()
+    .map(|member| (member.span().start.offset, member.span().end.offset))
+    .collect::<Vec<_>>();
+
+  for i in 0..=members.len() {
+    let gap_start = if i == 0 {
+      body.left_brace.end.offset
+    } else {
+      regions[i - 1].1
+    };
+    let gap_end = match members.get(i) {
+      Some(member) => member.span().start.offset,
+      None => body.right_brace.start.offset,
+    };
+    let first = comments.partition_point(|comment| comment.span.start.offset < gap_start);
+    let mut cursor = gap_start;
+    let mut leading_start = None;
+
+    for comment in comments[first..]
+      .iter()
+      .take_while(|comment| comment.span.end.offset <= gap_end)
+    {
+      let on_same_line = !text[cursor as usize..comment.span.start.offset as usize].contains(&b'\n');
+      if leading_start.is_none() && on_same_line {
+        // comments after the opening brace stay where they are
+        if i > 0 {
+          regions[i - 1].1 = comment.span.end.offset;
+        }
+        cursor = comment.span.end.offset;
+      } else if leading_start.is_none() {
+        leading_start = Some(comment.span.start.offset);
+      }
+    }
+
+    if let (Some(start), Some(region)) = (leading_start, regions.get_mut(i)) {
+      region.0 = start;
+    }
+  }
+
+  regions
+}
+
+/// Gets the text of the region at the index for moving it to the slot,
+/// breaking the line after it when it ends in a line comment and something
+/// follows the slot on the same line.
+pub(super) fn moved_region(program: &Program, regions: &[(u32, u32)], slot: usize, index: usize) -> Vec<u8> {
+  let text = program.source_text;
+  let (start, end) = regions[index];
+  let mut result = text[start as usize..end as usize].to_vec();
+  let ends_with_line_comment = program.trivia.iter().any(|trivia| {
+    trivia.span.end.offset == end && matches!(trivia.kind, TriviaKind::SingleLineComment | TriviaKind::HashComment)
+  });
+  let rest = &text[regions[slot].1 as usize..];
+  let ends_line = rest
+    .iter()
+    .find(|byte| !matches!(byte, b' ' | b'\t'))
+    .is_none_or(|byte| matches!(byte, b'\n' | b'\r'));
+  if ends_with_line_comment && !ends_line {
+    result.push(b'\n');
+  }
+  result
+}
+
+fn group_index(member: &ClassLikeMember, groups: &[ClassElementGroup]) -> usize {
+  candidate_groups(member)
+    .into_iter()
+    .find_map(|candidate| groups.iter().position(|group| *group == candidate))
+    .unwrap_or(groups.len())
+}
+
+/// Gets the groups a member belongs to, from the most to the least specific.
+fn candidate_groups(member: &ClassLikeMember) -> Vec<ClassElementGroup> {
+  use ClassElementGroup::*;
+
+  match member {
+    ClassLikeMember::TraitUse(_) => vec![UseTrait],
+    ClassLikeMember::EnumCase(_) => vec![Case],
+    ClassLikeMember::Constant(constant) => {
+      let visibility = match visibility(constant.modifiers.as_slice()) {
+        Visibility::Public => ConstantPublic,
+        Visibility::Protected => ConstantProtected,
+        Visibility::Private => ConstantPrivate,
+      };
+      vec![visibility, Constant]
+    }
+    ClassLikeMember::Property(property) => {
+      let modifiers = property.modifiers().as_slice();
+      let is_static = modifiers.iter().any(|modifier| modifier.is_static());
+      let is_readonly = modifiers.iter().any(|modifier| modifier.is_readonly());
+      let (by_visibility, static_, readonly) = match visibility(modifiers) {
+        Visibility::Public => (PropertyPublic, PropertyPublicStatic, PropertyPublicReadonly),
+        Visibility::Protected => (PropertyProtected, PropertyProtectedStatic, PropertyProtectedReadonly),
+        Visibility::Private => (PropertyPrivate, PropertyPrivateStatic, PropertyPrivateReadonly),
+      };
+      let mut candidates = Vec::new();
+      if is_static {
+        candidates.push(static_);
+      }
+      if is_readonly {
+        candidates.push(readonly);
+      }
+      candidates.push(by_visibility);
+      if is_static {
+        candidates.push(PropertyStatic);
+      }
+      if is_readonly {
+        candidates.push(PropertyReadonly);
+      }
+      candidates.push(Property);
+      candidates
+    }
+    ClassLikeMember::Method(method) => {
+      let modifiers = method.modifiers.as_slice();
+      let is_static = modifiers.iter().any(|modifier| modifier.is_static());
+      let is_abstract = modifiers.iter().any(|modifier| modifier.is_abstract());
+      let (by_visibility, static_, abstract_) = match visibility(modifiers) {
+        Visibility::Public => (MethodPublic, MethodPublicStatic, MethodPublicAbstract),
+        Visibility::Protected => (MethodProtected, MethodProtectedStatic, MethodProtectedAbstract),
+        Visibility::Private => (MethodPrivate, MethodPrivateStatic, MethodPrivateAbstract),
+      };
+      let mut candidates = Vec::new();
+      let name = method.name.value;
+      if name.eq_ignore_ascii_case(b"__construct") {
+        candidates.push(Construct);
+      } else if name.eq_ignore_ascii_case(b"__destruct") {
+        candidates.push(Destruct);
+      }
+      if name.starts_with(b"__") {
+        candidates.push(Magic);
+      }
+      if is_static {
+        candidates.push(static_);
+      }
+      if is_abstract {
+        candidates.push(abstract_);
+      }
+      candidates.push(by_visibility);
+      if is_static {
+        candidates.push(MethodStatic);
+      }
+      if is_abstract {
+        candidates.push(MethodAbstract);
+      }
+      candidates.push(Method);
+      candidates
+    }
+  }
+}
+
+enum Visibility {
+  Public,
+  Protected,
+  Private,
+}
+
+fn visibility(modifiers: &[Modifier]) -> Visibility {
+  for modifier in modifiers {
+    match modifier {
+      Modifier::Public(_) => return Visibility::Public,
+      Modifier::Protected(_) => return Visibility::Protected,
+      Modifier::Private(_) => return Visibility::Private,
+      _ => {}
+    }
+  }
+  Visibility::Public
+}
+
+fn member_name<'arena>(member: &ClassLikeMember<'arena>) -> &'arena [u8] {
+  match member {
+    ClassLikeMember::TraitUse(trait_use) => trait_use.trait_names.first().map(|name| name.value()).unwrap_or(b""),
+    ClassLikeMember::EnumCase(enum_case) => enum_case.item.name().value,
+    ClassLikeMember::Constant(constant) => constant.first_item().name.value,
+    ClassLikeMember::Property(property) => property.first_variable().name,
+    ClassLikeMember::Method(method) => method.name.value,
+  }
+}
+
+pub(super) fn compare_names(a: &[u8], b: &[u8], sort_order: SortOrder) -> Ordering {
+  let alphanumeric = || {
+    a.to_ascii_lowercase()
+      .cmp(&b.to_ascii_lowercase())
+      .then_with(|| a.cmp(b))
+  };
+  match sort_order {
+    SortOrder::Preserve => Ordering::Equal,
+    SortOrder::AlphanumericAscending => alphanumeric(),
+    SortOrder::AlphanumericDescending => alphanumeric().reverse(),
+    SortOrder::LengthAscending => a.len().cmp(&b.len()).then_with(alphanumeric),
+    SortOrder::LengthDescending => b.len().cmp(&a.len()).then_with(alphanumeric),
+  }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+
+use mago_allocator::LocalArena;
+use mago_span::HasSpan;
+use mago_span::Span;
+use mago_syntax::cst::Identifier;
+use mago_syntax::cst::Node;
+use mago_syntax::cst::Program;
+use mago_syntax::cst::Statement;
+use mago_syntax::cst::Use;
+use mago_syntax::cst::UseItem;
+use mago_syntax::cst::UseItems;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use super::import_scope;
+use super::parse;
+use super::trailing_comments_end;
+
+/// Removes `use` imports that nothing in the file references.
+///
+/// References are matched by name against code identifiers and the names
+/// found on docblock tag lines, so the file is left alone whenever an import
+/// could still be needed.
+pub fn transform(text: &[u8]) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+
+  let (_, statements) = import_scope(program)?;
+  let uses = statements
+    .iter()
+    .filter_map(|statement| match statement {
+      Statement::Use(r#use) => Some(r#use),
+      _ => None,
+    })
+    .collect::<Vec<_>>();
+  if uses.is_empty() {
+    return None;
+  }
+
+  let mut referenced = HashSet::new();
+  collect_references(Node::Program(program), &mut referenced);
+  collect_docblock_references(program, &mut referenced);
+
+  let mut editor = TextEditor::new(text);
+  for r#use in uses {
+    if let Some(edit) = remove_unused_items(program, r#use, &referenced) {
+      editor.apply(edit, None::<fn(&[u8]) -> bool>);
+    }
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
+
+fn remove_unused_items(program: &Program, r#use: &Use, referenced: &HashSet<Vec<u8>>) -> Option<TextEdit> {
+  if !r#use.terminator.is_semicolon() {
+    return None;
+  }
+
+  let items: Vec<(Span, &UseItem)> = match &r#use.items {
+    UseItems::Sequence(sequence) => sequence.items.iter().map(|item| (item.span(), item)).collect(),
+    UseItems::TypedSequence(sequence) => sequence.items.iter().map(|item| (item.span(), item)).collect(),
+    UseItems::TypedList(list) => list.items.iter().map(|item| (item.span(), item)).collect(),
+    UseItems::MixedList(list) => list.items.iter().map(|item| (item.span(), &item.item)).collect(),
+  };
+  let kept = items
+    .iter()
+    .filter(|(_, item)| referenced.contains(&imported_name(item).to_ascii_lowercase()))
+    .collect::<Vec<_>>();
+
+  if kept.len() == items.len() {
+    return None;
+  }
+
+  let text = program.source_text;
+  if kept.is_empty() {
+    return Some(TextEdit::delete(statement_line_range(program, r#use.span())));
+  }
+
+  // rewriting part of a list would drop any comments between its items
+  let start = items.first()?.0.start.offset;
+  let end = items.last()?.0.end.offset;
+  if program
+    .trivia
+    .iter()
+    .any(|trivia| trivia.kind.is_comment() && trivia.span.start.offset >= start && trivia.span.end.offset <= end)
+  {
+    return None;
+  }
+
+  let new_text = kept
+    .iter()
+    .map(|(span, _)| &text[span.start.offset as usize..span.end.offset as usize])
+    .collect::<Vec<_>>()
+    .join(&b", "[..]);
+  Some(TextEdit::replace(start..end, new_text))
+}
+
+fn imported_name<'arena>(item: &UseItem<'arena>) -> &'arena [u8] {
+  match &item.alias {
+    Some(alias) => alias.identifier.value,
+    None => item.name.last_segment(),
+  }
+}
+
+/// Gets the range of the statement and the comments after it on the same
+/// line, extended over its line when nothing else is on it so that no empty
+/// line is left behind.
+fn statement_line_range(program: &Program, span: Span) -> std::ops::Range<u32> {
+  let text = program.source_text;
+  let start = span.start.offset as usize;
+  let end = trailing_comments_end(program, span.end.offset) as usize;
+  let line_start = text[..start]
+    .iter()
+    .rposition(|byte| !matches!(byte, b' ' | b'\t'))
+    .map(|index| index + 1)
+    .unwrap_or(0);
+  let line_end = text[end..]
+    .iter()
+    .position(|byte| !matches!(byte, b' ' | b'\t' | b'\r'))
+    .map(|index| end + index)
+    .unwrap_or(text.len());
+
+  if (line_start == 0 || text[line_start - 1] == b'\n') && text.get(line_end) == Some(&b'\n') {
+    line_start as u32..line_end as u32 + 1
+  } else {
+    span.start.offset..end as u32
+  }
+}
+
+/// Collects the lowercased first segment of every name referenced in code.
+fn collect_references(node: Node, referenced: &mut HashSet<Vec<u8>>) {
+  match node {
+    Node::Use(_) => return,
+    Node::Namespace(namespace) => {
+      for statement in namespace.statements().iter() {
+        collect_references(Node::Statement(statement), referenced);
+      }
+      return;
+    }
+    Node::Identifier(identifier) if !matches!(identifier, Identifier::FullyQualified(_)) => {
+      referenced.insert(first_segment(identifier.value()).to_ascii_lowercase());
+    }
+    _ => {}
+  }
+
+  node.visit_children(|child| collect_references(child, referenced));
+}
+
+/// Collects the names on tag lines of comments (ex. `@param Foo $foo` or
+/// `{@see Foo}`) and on the lines that follow a tag, such as those of a
+/// multi-line `array{...}` type, which may refer to imports that the code
+/// doesn't use.
+pub(super) fn collect_docblock_references(program: &Program, referenced: &mut HashSet<Vec<u8>>) {
+  for comment in program.trivia.iter().filter(|trivia| trivia.kind.is_comment()) {
+    let mut in_tag = false;
+    for line in comment.value.split(|byte| *byte == b'\n') {
+      let line = match line.iter().position(|byte| *byte == b'@') {
+        Some(tag_start) => {
+          in_tag = true;
+          &line[tag_start + 1..]
+        }
+        None if in_tag => line,
+        None => continue,
+      };
+      let mut index = 0;
+      while index < line.len() {
+        let is_name_start = line[index].is_ascii_alphabetic() || line[index] == b'_' || line[index] == b'\\';
+        let follows_name = index > 0 && (is_name_byte(line[index - 1]) || line[index - 1] == b'$');
+        if !is_name_start || follows_name {
+          index += 1;
+          continue;
+        }
+
+        let end = line[index..]
+          .iter()
+          .position(|byte| !is_name_byte(*byte))
+          .map(|length| index + length)
+          .unwrap_or(line.len());
+        let name = &line[index..end];
+        if !name.starts_with(b"\\") {
+          referenced.insert(first_segment(name).to_ascii_lowercase());
+        }
+        index = end;
+      }
+    }
+  }
+}
+
+fn is_name_byte(byte: u8) -> bool {
+  byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'\\' || byte >= 0x80
+}
+
+fn first_segment(name: &[u8]) -> &[u8] {
+  name.split(|byte| *byte == b'\\').next().unwrap_or(name)
+}
@@ -0,0 +1,119 @@
+use mago_allocator::LocalArena;
+use mago_span::HasSpan;
+use mago_syntax::cst::ForBody;
+use mago_syntax::cst::ForeachBody;
+use mago_syntax::cst::IfBody;
+use mago_syntax::cst::Node;
+use mago_syntax::cst::Program;
+use mago_syntax::cst::Statement;
+use mago_syntax::cst::WhileBody;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use super::parse;
+use super::trailing_comments_end;
+
+/// A brace-less body to wrap in a block.
+struct Body {
+  /// Where the opening brace goes, which is after the parenthesis or keyword
+  /// before the body so that comments there end up inside the block.
+  open: u32,
+  /// The end of the body statement and the comments trailing it.
+  end: u32,
+  /// Whether the body is an empty statement (`;`) that's replaced.
+  empty: Option<(u32, u32)>,
+}
+
+/// Wraps the brace-less bodies of control structures in blocks, which the
+/// formatter then lays out with the control brace style.
+///
+/// `else if` is left alone, as are bodies with PHP tags in them.
+pub fn transform(text: &[u8]) -> Option<Vec<u8>> {
+  let mut result: Option<Vec<u8>> = None;
+
+  // a body nested in another one (ex. `if ($a) if ($b) foo();`) ends where
+  // the outer body ends, so it's wrapped on a following pass
+  loop {
+    let current = result.as_deref().unwrap_or(text);
+    let arena = LocalArena::new();
+    let program = parse(&arena, current)?;
+    let mut bodies = brace_less_bodies(program);
+    if bodies.is_empty() {
+      return result;
+    }
+
+    bodies.sort_by_key(|body| body.open);
+    let mut editor = TextEditor::new(current);
+    let mut outer_end = 0;
+    for body in bodies {
+      if body.open < outer_end {
+        continue;
+      }
+      outer_end = body.end;
+      let edits = match body.empty {
+        Some((start, end)) => vec![TextEdit::replace(start..end, " {}")],
+        None => vec![TextEdit::insert(body.open, " {"), TextEdit::insert(body.end, "\n}")],
+      };
+      editor.apply_batch(edits, None::<fn(&[u8]) -> bool>);
+    }
+    result = Some(editor.finish());
+  }
+}
+
+fn brace_less_bodies(program: &Program) -> Vec<Body> {
+  let text = program.source_text;
+  let candidates = Node::Program(program).filter_map(|node| {
+    let mut candidates = Vec::new();
+    match node {
+      Node::If(r#if) => {
+        if let IfBody::Statement(body) = &r#if.body {
+          candidates.push((r#if.right_parenthesis.end.offset, body.statement));
+          for clause in body.else_if_clauses.iter() {
+            candidates.push((clause.right_parenthesis.end.offset, clause.statement));
+          }
+          if let Some(clause) = &body.else_clause
+            && !matches!(clause.statement, Statement::If(_))
+          {
+            candidates.push((clause.r#else.span.end.offset, clause.statement));
+          }
+        }
+      }
+      Node::For(r#for) => {
+        if let ForBody::Statement(statement) = &r#for.body {
+          candidates.push((r#for.right_parenthesis.end.offset, *statement));
+        }
+      }
+      Node::Foreach(foreach) => {
+        if let ForeachBody::Statement(statement) = &foreach.body {
+          candidates.push((foreach.right_parenthesis.end.offset, *statement));
+        }
+      }
+      Node::While(r#while) => {
+        if let WhileBody::Statement(statement) = &r#while.body {
+          candidates.push((r#while.right_parenthesis.end.offset, *statement));
+        }
+      }
+      Node::DoWhile(do_while) => candidates.push((do_while.r#do.span.end.offset, do_while.statement)),
+      _ => {}
+    }
+    Some(candidates)
+  });
+
+  candidates
+    .into_iter()
+    .flatten()
+    .filter(|(_, statement)| !matches!(statement, Statement::Block(_)))
+    .filter_map(|(open, statement)| {
+      let span = statement.span();
+      let statement_text = &text[span.start.offset as usize..span.end.offset as usize];
+      if statement_text.windows(2).any(|window| window == b"?>") {
+        return None;
+      }
+      Some(Body {
+        open,
+        end: trailing_comments_end(program, span.end.offset),
+        empty: matches!(statement, Statement::Noop(_)).then_some((open, span.end.offset)),
+      })
+    })
+    .collect()
+}
@@ -0,0 +1,56 @@
+use mago_allocator::LocalArena;
+use mago_syntax::cst::InlineKind;
+use mago_syntax::cst::Statement;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use super::file_header;
+use super::parse;
+
+const DECLARATION: &[u8] = b"\n\ndeclare(strict_types=1);\n\n";
+
+/// Adds `declare(strict_types=1);` after the opening tag and file header
+/// comment of files that don't declare strict types.
+///
+/// The formatter lays out the inserted declaration, so it follows the
+/// opening tag and declare blank line settings. Templates that are mostly
+/// inline HTML are left alone.
+pub fn transform(text: &[u8]) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let statements = program.statements.as_slice();
+
+  let has_strict_types = statements.iter().any(|statement| match statement {
+    Statement::Declare(declare) => declare
+      .items
+      .iter()
+      .any(|item| item.name.value.eq_ignore_ascii_case(b"strict_types")),
+    _ => false,
+  });
+  if has_strict_types {
+    return None;
+  }
+
+  let inline_length = statements
+    .iter()
+    .filter_map(|statement| match statement {
+      Statement::Inline(inline) if inline.kind == InlineKind::Text => Some(inline.value.len()),
+      _ => None,
+    })
+    .sum::<usize>();
+  if inline_length * 2 > text.len() {
+    return None;
+  }
+
+  // the declaration must be the first statement
+  let header = file_header(program)?;
+  let offset = header
+    .comments
+    .last()
+    .map(|comment| comment.span.end.offset)
+    .unwrap_or(header.tag_end);
+
+  let mut editor = TextEditor::new(text);
+  editor.apply(TextEdit::insert(offset, DECLARATION), None::<fn(&[u8]) -> bool>);
+  Some(editor.finish())
+}
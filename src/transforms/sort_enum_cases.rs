@@ -0,0 +1,67 @@
+use mago_allocator::LocalArena;
+use mago_syntax::cst::ClassLikeMember;
+use mago_syntax::cst::Node;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use crate::configuration::SortOrder;
+
+use super::ordered_class_elements::ClassLikeBody;
+use super::ordered_class_elements::compare_names;
+use super::ordered_class_elements::member_regions;
+use super::ordered_class_elements::moved_region;
+use super::parse;
+
+/// Sorts the cases of backed enums by name, moving each case together with
+/// its comments. Other members keep their positions.
+///
+/// This changes the order `cases()` returns. Pure enums are left alone.
+pub fn transform(text: &[u8], sort_order: SortOrder) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut editor = TextEditor::new(text);
+
+  let bodies = Node::Program(program).filter_map(|node| match node {
+    Node::Enum(r#enum) if r#enum.backing_type_hint.is_some() => Some(ClassLikeBody {
+      left_brace: r#enum.left_brace,
+      members: &r#enum.members,
+      right_brace: r#enum.right_brace,
+    }),
+    _ => None,
+  });
+
+  for body in bodies {
+    let cases = body
+      .members
+      .iter()
+      .enumerate()
+      .filter_map(|(index, member)| match member {
+        ClassLikeMember::EnumCase(case) => Some((index, case.item.name().value)),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    let mut order = cases.clone();
+    order.sort_by(|(_, a), (_, b)| compare_names(a, b, sort_order));
+    if order == cases {
+      continue;
+    }
+
+    let regions = member_regions(program, &body);
+    let edits = cases
+      .iter()
+      .zip(&order)
+      .filter(|((slot, _), (index, _))| slot != index)
+      .map(|((slot, _), (index, _))| {
+        let (start, end) = regions[*slot];
+        TextEdit::replace(start..end, moved_region(program, &regions, *slot, *index))
+      })
+      .collect::<Vec<_>>();
+    editor.apply_batch(edits, None::<fn(&[u8]) -> bool>);
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
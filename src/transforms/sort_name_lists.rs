@@ -0,0 +1,195 @@
+use mago_allocator::LocalArena;
+use mago_span::HasSpan;
+use mago_syntax::cst::Identifier;
+use mago_syntax::cst::Node;
+use mago_syntax::cst::Program;
+use mago_syntax::cst::TokenSeparatedSequence;
+use mago_syntax::cst::Trivia;
+use mago_syntax::cst::TriviaKind;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use crate::configuration::SortOrder;
+
+use super::ordered_class_elements::compare_names;
+use super::parse;
+
+/// A name in a list along with the comments that stay with it.
+struct Entry<'a> {
+  /// The comments on the lines before the name, up to the name.
+  leading: &'a [u8],
+  name: &'a [u8],
+  /// The comments after the name on the same line.
+  trailing: Vec<&'a Trivia<'a>>,
+  start: u32,
+  end: u32,
+}
+
+/// Sorts the names of `implements` and interface `extends` lists and of
+/// trait `use` statements.
+///
+/// Comments on the lines before a name and after it on the same line move
+/// with it. Lists with a trailing comma or other comments are left alone.
+pub fn transform(text: &[u8], implements_order: SortOrder, trait_use_order: SortOrder) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut editor = TextEditor::new(text);
+
+  let lists = Node::Program(program).filter_map(|node| match node {
+    Node::Implements(implements) => Some((
+      implements.implements.span.end.offset,
+      &implements.types,
+      implements_order,
+    )),
+    Node::Extends(extends) => Some((extends.extends.span.end.offset, &extends.types, implements_order)),
+    Node::TraitUse(trait_use) => Some((trait_use.r#use.span.end.offset, &trait_use.trait_names, trait_use_order)),
+    _ => None,
+  });
+
+  for (list_start, names, sort_order) in lists {
+    if sort_order == SortOrder::Preserve {
+      continue;
+    }
+    if let Some(edit) = sort_edit(program, list_start, names, sort_order) {
+      editor.apply(edit, None::<fn(&[u8]) -> bool>);
+    }
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
+
+fn sort_edit(
+  program: &Program,
+  list_start: u32,
+  names: &TokenSeparatedSequence<Identifier>,
+  sort_order: SortOrder,
+) -> Option<TextEdit> {
+  if names.len() < 2 || names.has_trailing_token() {
+    return None;
+  }
+
+  let text = program.source_text;
+  let entries = entries(program, list_start, names)?;
+  let mut order = (0..entries.len()).collect::<Vec<_>>();
+  order.sort_by(|a, b| compare_names(entries[*a].name, entries[*b].name, sort_order));
+  if order.iter().enumerate().all(|(slot, index)| slot == *index) {
+    return None;
+  }
+
+  let start = entries.first()?.start;
+  let end = entries.last()?.end;
+  let mut result = Vec::new();
+  for (slot, index) in order.iter().enumerate() {
+    let entry = &entries[*index];
+    result.extend_from_slice(entry.leading);
+    result.extend_from_slice(entry.name);
+    if slot + 1 < entries.len() {
+      result.push(b',');
+    }
+    for comment in &entry.trailing {
+      result.push(b' ');
+      result.extend_from_slice(comment.value);
+    }
+
+    let gap = match entries.get(slot + 1) {
+      Some(next) => &text[entries[slot].end as usize..next.start as usize],
+      None => {
+        let rest = &text[end as usize..];
+        &rest[..rest.iter().take_while(|byte| byte.is_ascii_whitespace()).count()]
+      }
+    };
+    // a line comment would comment out what follows it on the same line
+    let ends_with_line_comment = entry
+      .trailing
+      .last()
+      .is_some_and(|comment| matches!(comment.kind, TriviaKind::SingleLineComment | TriviaKind::HashComment));
+    if ends_with_line_comment && !gap.contains(&b'\n') {
+      result.push(b'\n');
+    }
+    if slot + 1 < entries.len() {
+      result.extend_from_slice(gap);
+    }
+  }
+
+  Some(TextEdit::replace(start..end, result))
+}
+
+/// Splits the list into its entries, or returns `None` when there are
+/// comments that don't belong to a name.
+fn entries<'a>(
+  program: &'a Program<'a>,
+  list_start: u32,
+  names: &TokenSeparatedSequence<'a, Identifier<'a>>,
+) -> Option<Vec<Entry<'a>>> {
+  let text = program.source_text;
+  let comments = program
+    .trivia
+    .iter()
+    .filter(|trivia| trivia.kind.is_comment())
+    .collect::<Vec<_>>();
+  let nodes = names.as_slice();
+
+  let mut entries: Vec<Entry> = Vec::with_capacity(nodes.len());
+  let mut cursor = list_start;
+  for (index, name) in nodes.iter().enumerate() {
+    let span = name.span();
+    let next_start = nodes.get(index + 1).map(|next| next.span().start.offset);
+
+    // comments on the lines before the name
+    let mut start = span.start.offset;
+    let before = comments
+      .iter()
+      .filter(|comment| comment.span.start.offset >= cursor && comment.span.end.offset <= span.start.offset);
+    for comment in before {
+      if index > 0 && !text[cursor as usize..comment.span.start.offset as usize].contains(&b'\n') {
+        return None;
+      }
+      start = start.min(comment.span.start.offset);
+    }
+
+    // comments after the name on the same line, before the next name
+    let mut end = span.end.offset;
+    let mut trailing = Vec::new();
+    for comment in comments.iter().filter(|comment| {
+      comment.span.start.offset >= span.end.offset && next_start.is_none_or(|next| comment.span.end.offset <= next)
+    }) {
+      // stop at the next line or at the end of the list
+      let between = &text[end as usize..comment.span.start.offset as usize];
+      if !between.iter().all(|byte| matches!(byte, b' ' | b'\t' | b',')) {
+        break;
+      }
+      trailing.push(*comment);
+      end = comment.span.end.offset;
+    }
+    // the entry ends after its comma
+    if let Some(comma) = names.tokens.get(index) {
+      end = end.max(comma.start.offset + comma.value.len() as u32);
+    }
+
+    entries.push(Entry {
+      leading: &text[start as usize..span.start.offset as usize],
+      name: &text[span.start.offset as usize..span.end.offset as usize],
+      trailing,
+      start,
+      end,
+    });
+    cursor = end;
+  }
+
+  // anything left between the entries other than whitespace is a comment
+  // that isn't next to a name
+  for pair in entries.windows(2) {
+    if !text[pair[0].end as usize..pair[1].start as usize]
+      .iter()
+      .all(u8::is_ascii_whitespace)
+    {
+      return None;
+    }
+  }
+
+  Some(entries)
+}
@@ -0,0 +1,244 @@
+use mago_allocator::LocalArena;
+use mago_syntax::cst::Trivia;
+use mago_syntax::cst::TriviaKind;
+use mago_text_edit::TextEdit;
+use mago_text_edit::TextEditor;
+
+use super::docblocks::toggles_code;
+use super::parse;
+
+pub struct WrapOptions {
+  pub print_width: usize,
+  pub tab_width: usize,
+}
+
+/// A comment line split into its prefix (such as `// ` or ` * `) and text.
+struct Line<'a> {
+  prefix: &'a str,
+  text: &'a str,
+}
+
+/// Reflows the paragraphs of `//` comment runs and docblock descriptions that
+/// have a line past the print width.
+///
+/// List items, code samples, `@tag` lines, indented lines, lines with URLs and
+/// commented-out code are kept as they are. This runs on the formatted output
+/// so that the final indentation of each comment is known.
+pub fn transform(text: &[u8], options: &WrapOptions) -> Option<Vec<u8>> {
+  let arena = LocalArena::new();
+  let program = parse(&arena, text)?;
+  let mut editor = TextEditor::new(text);
+  let newline = if text.windows(2).any(|window| window == b"\r\n") {
+    "\r\n"
+  } else {
+    "\n"
+  };
+
+  let mut runs: Vec<Vec<&Trivia>> = Vec::new();
+  for comment in program.trivia.iter() {
+    let Some(indent) = own_line_indent(text, comment) else {
+      continue;
+    };
+    match comment.kind {
+      TriviaKind::SingleLineComment => {
+        let previous_end = runs.last().and_then(|run| run.last()).map(|last| last.span.end.offset);
+        let between = previous_end.map(|end| &text[end as usize..comment.span.start.offset as usize]);
+        let continues_run = between.is_some_and(|between| {
+          between
+            .strip_prefix(newline.as_bytes())
+            .is_some_and(|rest| rest == indent.as_bytes())
+        });
+        match runs.last_mut() {
+          Some(run) if continues_run => run.push(comment),
+          _ => runs.push(vec![comment]),
+        }
+      }
+      TriviaKind::DocBlockComment => {
+        if let Some(wrapped) = wrap_docblock(comment, newline, options) {
+          editor.apply(
+            TextEdit::replace(comment.span.start.offset..comment.span.end.offset, wrapped),
+            None::<fn(&[u8]) -> bool>,
+          );
+        }
+      }
+      _ => {}
+    }
+  }
+
+  for run in runs {
+    let (Some(first), Some(last)) = (run.first(), run.last()) else {
+      continue;
+    };
+    let Some(indent) = own_line_indent(text, first) else {
+      continue;
+    };
+    let Some(lines) = run
+      .iter()
+      .map(|comment| {
+        let value = std::str::from_utf8(comment.value).ok()?;
+        let text_start = 2 + usize::from(value[2..].starts_with(' '));
+        Some(Line {
+          prefix: &value[..text_start],
+          text: &value[text_start..],
+        })
+      })
+      .collect::<Option<Vec<_>>>()
+    else {
+      continue;
+    };
+
+    if let Some(wrapped) = reflow(&lines, indent, options) {
+      let wrapped = wrapped.join(&format!("{newline}{indent}"));
+      editor.apply(
+        TextEdit::replace(first.span.start.offset..last.span.end.offset, wrapped),
+        None::<fn(&[u8]) -> bool>,
+      );
+    }
+  }
+
+  if editor.get_edits().is_empty() {
+    None
+  } else {
+    Some(editor.finish())
+  }
+}
+
+/// Gets the indentation before a comment that starts its own line.
+fn own_line_indent<'a>(text: &'a [u8], comment: &Trivia) -> Option<&'a str> {
+  let start = comment.span.start.offset as usize;
+  let line_start = text[..start]
+    .iter()
+    .rposition(|byte| *byte == b'\n')
+    .map(|index| index + 1)
+    .unwrap_or(0);
+  let indent = std::str::from_utf8(&text[line_start..start]).ok()?;
+  indent.chars().all(|c| c == ' ' || c == '\t').then_some(indent)
+}
+
+/// Reflows the description of a multi-line docblock, which is the text
+/// before its first tag.
+fn wrap_docblock(comment: &Trivia, newline: &str, options: &WrapOptions) -> Option<String> {
+  let value = std::str::from_utf8(comment.value).ok()?;
+  let mut lines = value.split(newline);
+  if lines.next()? != "/**" {
+    return None;
+  }
+  let mut body = lines.collect::<Vec<_>>();
+  let closing = body.pop()?;
+  if closing.trim_start() != "*/" {
+    return None;
+  }
+
+  let mut description = Vec::new();
+  for line in &body {
+    let trimmed = line.trim_start();
+    let rest = trimmed.strip_prefix('*')?;
+    let gutter_end = line.len() - rest.len() + usize::from(rest.starts_with(' '));
+    let line = Line {
+      prefix: &line[..gutter_end],
+      text: &line[gutter_end..],
+    };
+    if line.text.starts_with('@') {
+      break;
+    }
+    description.push(line);
+  }
+
+  // the gutter prefixes already include the indentation
+  let wrapped = reflow(&description, "", options)?;
+  let mut result = vec!["/**".to_string()];
+  result.extend(wrapped);
+  result.extend(body[description.len()..].iter().map(|line| line.to_string()));
+  result.push(closing.to_string());
+  Some(result.join(newline))
+}
+
+/// Reflows the paragraphs of the lines, returning the prefixed lines or
+/// `None` when no paragraph needed it.
+fn reflow(lines: &[Line], indent: &str, options: &WrapOptions) -> Option<Vec<String>> {
+  let indent_width = indent
+    .chars()
+    .map(|c| if c == '\t' { options.tab_width } else { 1 })
+    .sum::<usize>();
+  let available = |prefix: &str| {
+    let prefix_width = prefix
+      .chars()
+      .map(|c| if c == '\t' { options.tab_width } else { 1 })
+      .sum::<usize>();
+    options.print_width.saturating_sub(indent_width + prefix_width)
+  };
+
+  let mut result = Vec::new();
+  let mut changed = false;
+  let mut paragraph: Vec<&Line> = Vec::new();
+  let mut in_code = false;
+  let mut flush = |paragraph: &mut Vec<&Line>, result: &mut Vec<String>| {
+    let Some(first) = paragraph.first() else {
+      return;
+    };
+    let width = available(first.prefix);
+    if paragraph.iter().any(|line| line.text.chars().count() > width) {
+      let words = paragraph.iter().flat_map(|line| line.text.split_whitespace());
+      result.extend(
+        fill(words, width)
+          .into_iter()
+          .map(|line| format!("{}{line}", first.prefix)),
+      );
+      changed = true;
+    } else {
+      result.extend(paragraph.iter().map(|line| format!("{}{}", line.prefix, line.text)));
+    }
+    paragraph.clear();
+  };
+
+  for line in lines {
+    let keep = in_code || is_kept_whole(line.text);
+    in_code = toggles_code(line.text, in_code);
+    if keep {
+      flush(&mut paragraph, &mut result);
+      result.push(format!("{}{}", line.prefix, line.text));
+    } else {
+      paragraph.push(line);
+    }
+  }
+  flush(&mut paragraph, &mut result);
+
+  changed.then_some(result)
+}
+
+/// Gets whether a line isn't joined with the lines around it, which is the
+/// case for blank lines, tags, list items, code fences, indented lines, lines
+/// with URLs and lines that look like code.
+fn is_kept_whole(text: &str) -> bool {
+  let first_word = text.split(' ').next().unwrap_or_default();
+  text.trim().is_empty()
+    || text.starts_with([' ', '\t', '@', '$'])
+    || text.trim_end().ends_with([';', '{', '}', ')'])
+    || text.starts_with("```")
+    || text.contains("<code>")
+    || text.contains("://")
+    || matches!(first_word, "-" | "*" | "+")
+    || first_word
+      .strip_suffix(['.', ')'])
+      .is_some_and(|number| !number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit()))
+}
+
+/// Fills lines with the words, starting a new line before a word that would
+/// go past the width. Words wider than the width get a line of their own.
+fn fill<'a>(words: impl Iterator<Item = &'a str>, width: usize) -> Vec<String> {
+  let mut lines = Vec::new();
+  let mut current = String::new();
+  for word in words {
+    if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+      lines.push(std::mem::take(&mut current));
+    }
+    if !current.is_empty() {
+      current.push(' ');
+    }
+    current.push_str(word);
+  }
+  if !current.is_empty() {
+    lines.push(current);
+  }
+  lines
+}
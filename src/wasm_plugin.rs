@@ -1,12 +1,10 @@
-use super::configuration::Configuration;
-use super::configuration::resolve_config;
+use super::plugin_handler::*;
 
 use dprint_core::configuration::ConfigKeyMap;
 use dprint_core::configuration::GlobalConfiguration;
 use dprint_core::generate_plugin_code;
 use dprint_core::plugins::CheckConfigUpdatesMessage;
 use dprint_core::plugins::ConfigChange;
-use dprint_core::plugins::FileMatchingInfo;
 use dprint_core::plugins::FormatResult;
 use dprint_core::plugins::PluginInfo;
 use dprint_core::plugins::PluginResolveConfigurationResult;
@@ -14,24 +12,13 @@ use dprint_core::plugins::SyncFormatRequest;
 use dprint_core::plugins::SyncHostFormatRequest;
 use dprint_core::plugins::SyncPluginHandler;
 
-struct MagoPluginHandler;
-
-impl SyncPluginHandler<Configuration> for MagoPluginHandler {
+impl SyncPluginHandler<PluginConfiguration> for MagoPluginHandler {
   fn resolve_config(
     &mut self,
     config: ConfigKeyMap,
     global_config: &GlobalConfiguration,
-  ) -> PluginResolveConfigurationResult<Configuration> {
-    let result = resolve_config(config, global_config);
-    let file_extensions = vec!["php".to_string()];
-    PluginResolveConfigurationResult {
-      config: result.config,
-      diagnostics: result.diagnostics,
-      file_matching: FileMatchingInfo {
-        file_extensions,
-        file_names: vec![],
-      },
-    }
+  ) -> PluginResolveConfigurationResult<PluginConfiguration> {
+    resolve_plugin_config(config, global_config)
   }
 
   fn check_config_updates(&self, _message: CheckConfigUpdatesMessage) -> anyhow::Result<Vec<ConfigChange>> {
@@ -39,37 +26,24 @@ impl SyncPluginHandler<Configuration> for MagoPluginHandler {
   }
 
   fn plugin_info(&mut self) -> PluginInfo {
-    let version = env!("CARGO_PKG_VERSION").to_string();
-    PluginInfo {
-      name: env!("CARGO_PKG_NAME").to_string(),
-      version: version.clone(),
-      config_key: "mago".to_string(),
-      help_url: "https://dprint.dev/plugins/mago".to_string(),
-      config_schema_url: format!(
-        "https://plugins.dprint.dev/dprint/dprint-plugin-mago/{}/schema.json",
-        version
-      ),
-      update_url: Some("https://plugins.dprint.dev/dprint/dprint-plugin-mago/latest.json".to_string()),
-    }
+    plugin_info()
   }
 
   fn license_text(&mut self) -> String {
-    std::str::from_utf8(include_bytes!("../LICENSE")).unwrap().into()
+    license_text()
   }
 
   fn format(
     &mut self,
-    request: SyncFormatRequest<Configuration>,
+    request: SyncFormatRequest<PluginConfiguration>,
     _format_with_host: impl FnMut(SyncHostFormatRequest) -> FormatResult,
   ) -> FormatResult {
     if request.range.is_some() {
       return Ok(None); // range formatting not implemented
     }
 
-    let text = String::from_utf8_lossy(&request.file_bytes);
-    let maybe_text = super::format_text(request.file_path, &text, request.config)?;
-    Ok(maybe_text.map(|t| t.into_bytes()))
+    format_file_bytes(request.file_path, request.file_bytes, request.config)
   }
 }
 
-generate_plugin_code!(MagoPluginHandler, MagoPluginHandler);
+generate_plugin_code!(MagoPluginHandler, MagoPluginHandler, PluginConfiguration);
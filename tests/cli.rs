@@ -0,0 +1,116 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Output;
+use std::process::Stdio;
+
+fn run_cli(args: &[&str], stdin_text: &str) -> Output {
+  let mut child = Command::new(env!("CARGO_BIN_EXE_dprint-plugin-mago-cli"))
+    .args(args)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap();
+  child.stdin.take().unwrap().write_all(stdin_text.as_bytes()).unwrap();
+  child.wait_with_output().unwrap()
+}
+
+fn write_temp_config(name: &str, text: &str) -> PathBuf {
+  let path = std::env::temp_dir().join(format!("dprint-plugin-mago-cli-{}-{}.json", std::process::id(), name));
+  std::fs::write(&path, text).unwrap();
+  path
+}
+
+#[test]
+fn should_write_formatted_text_and_exit_with_changed() {
+  let output = run_cli(&[], "<?php\necho   'hello'  ;");
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "<?php\n\necho 'hello';\n");
+}
+
+#[test]
+fn should_write_input_and_exit_with_unchanged() {
+  let output = run_cli(&[], "<?php\n\necho 'hello';\n");
+  assert_eq!(output.status.code(), Some(0));
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "<?php\n\necho 'hello';\n");
+}
+
+#[test]
+fn should_not_write_output_when_checking() {
+  let output = run_cli(&["--check"], "<?php\necho   'hello'  ;");
+  assert_eq!(output.status.code(), Some(1));
+  assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn should_exit_with_syntax_error() {
+  let output = run_cli(&[], "<?php\nfunction test( {}");
+  assert_eq!(output.status.code(), Some(2));
+  assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn should_not_format_other_extensions() {
+  let output = run_cli(&["--file-path", "file.js"], "const   x = 1;");
+  assert_eq!(output.status.code(), Some(0));
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "const   x = 1;");
+}
+
+#[test]
+fn should_use_mago_section_of_dprint_config() {
+  let config_path = write_temp_config(
+    "dprint",
+    r#"{ "indentWidth": 2, "mago": { "singleQuote": false }, "plugins": [] }"#,
+  );
+  let output = run_cli(
+    &["--config", config_path.to_str().unwrap()],
+    "<?php\nfunction test(){return 'a';}",
+  );
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "<?php\n\nfunction test()\n{\n  return \"a\";\n}\n"
+  );
+}
+
+#[test]
+fn should_allow_comments_in_dprint_config() {
+  let config_path = write_temp_config(
+    "dprint-comments",
+    "{\n  // formatting settings\n  \"indentWidth\": 2,\n  /* php */\n  \"mago\": {},\n}\n",
+  );
+  let output = run_cli(
+    &["--config", config_path.to_str().unwrap()],
+    "<?php\nfunction test(){return 1;}",
+  );
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "<?php\n\nfunction test()\n{\n  return 1;\n}\n"
+  );
+}
+
+#[test]
+fn should_use_plugin_config_file() {
+  let config_path = write_temp_config("mago", r#"{ "tabWidth": 2 }"#);
+  let output = run_cli(
+    &["--config", config_path.to_str().unwrap()],
+    "<?php\nfunction test(){return 1;}",
+  );
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "<?php\n\nfunction test()\n{\n  return 1;\n}\n"
+  );
+}
+
+#[test]
+fn should_exit_with_error_for_invalid_config() {
+  let config_path = write_temp_config("invalid", r#"{ "unknownProperty": true }"#);
+  let output = run_cli(&["--config", config_path.to_str().unwrap()], "<?php\n");
+  assert_eq!(output.status.code(), Some(3));
+  assert!(String::from_utf8(output.stderr).unwrap().contains("unknownProperty"));
+}
@@ -0,0 +1,137 @@
+#![cfg(feature = "process")]
+
+extern crate dprint_development;
+
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use anyhow::Result;
+use dprint_core::configuration::*;
+use dprint_core::plugins::FormatConfigId;
+use dprint_core::plugins::NullCancellationToken;
+use dprint_core::plugins::process::ProcessPluginCommunicator;
+use dprint_core::plugins::process::ProcessPluginCommunicatorFormatRequest;
+use dprint_development::*;
+use tokio::sync::mpsc;
+
+struct FormatMessage {
+  file_path: PathBuf,
+  file_text: String,
+  config: ConfigKeyMap,
+  /// The text is formatted once with each override config.
+  override_configs: Vec<ConfigKeyMap>,
+  response: std::sync::mpsc::Sender<Result<Vec<Option<String>>>>,
+}
+
+/// Runs the process plugin binary on a separate thread, since the
+/// communicator must stay on the single-threaded runtime that created it.
+fn start_process_plugin() -> mpsc::UnboundedSender<FormatMessage> {
+  let (tx, mut rx) = mpsc::unbounded_channel::<FormatMessage>();
+  std::thread::spawn(move || {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+      .enable_time()
+      .build()
+      .unwrap();
+    runtime.block_on(async move {
+      let executable_file_path = PathBuf::from(env!("CARGO_BIN_EXE_dprint-plugin-mago"));
+      let communicator = ProcessPluginCommunicator::new(&executable_file_path, |text| eprintln!("{}", text))
+        .await
+        .unwrap();
+      let global_config = GlobalConfiguration::default();
+      let mut next_config_id = 0;
+
+      while let Some(message) = rx.recv().await {
+        next_config_id += 1;
+        let config_id = FormatConfigId::from_raw(next_config_id);
+        let result = async {
+          communicator
+            .register_config(config_id, &global_config, &message.config)
+            .await?;
+          let diagnostics = communicator.config_diagnostics(config_id).await?;
+          ensure_no_diagnostics(&diagnostics);
+          let mut results = Vec::new();
+          for override_config in message.override_configs {
+            let result = communicator
+              .format_text(ProcessPluginCommunicatorFormatRequest {
+                file_path: message.file_path.clone(),
+                file_bytes: message.file_text.clone().into_bytes(),
+                range: None,
+                config_id,
+                override_config,
+                on_host_format: Rc::new(|_| panic!("Plugin does not host format.")),
+                token: Arc::new(NullCancellationToken),
+              })
+              .await;
+            results.push(result.map(|bytes| bytes.map(|bytes| String::from_utf8(bytes).unwrap())));
+          }
+          communicator.release_config(config_id).await?;
+          results.into_iter().collect::<Result<Vec<_>>>()
+        }
+        .await;
+        message.response.send(result).unwrap();
+      }
+    });
+  });
+  tx
+}
+
+#[test]
+fn test_specs_process_plugin() {
+  let sender = start_process_plugin();
+
+  run_specs(
+    &PathBuf::from("./tests/specs"),
+    &ParseSpecOptions {
+      default_file_name: "file.php",
+    },
+    &RunSpecsOptions {
+      fix_failures: false,
+      format_twice: true,
+    },
+    Arc::new(move |file_path, file_text, spec_config| {
+      let config: ConfigKeyMap = serde_json::from_value(spec_config.clone().into()).unwrap();
+      let (tx, rx) = std::sync::mpsc::channel();
+      sender
+        .send(FormatMessage {
+          file_path: file_path.to_path_buf(),
+          file_text: file_text.to_string(),
+          config,
+          override_configs: vec![ConfigKeyMap::new()],
+          response: tx,
+        })
+        .unwrap();
+      rx.recv().unwrap().map(|mut results| results.remove(0))
+    }),
+    Arc::new(move |_file_path, _file_text, _spec_config| panic!("Plugin does not support dprint-core tracing.")),
+  )
+}
+
+#[test]
+fn test_override_config_process_plugin() {
+  let sender = start_process_plugin();
+  let mut override_config = ConfigKeyMap::new();
+  override_config.insert("printWidth".to_string(), ConfigKeyValue::from_i32(16));
+
+  let (tx, rx) = std::sync::mpsc::channel();
+  sender
+    .send(FormatMessage {
+      file_path: PathBuf::from("file.php"),
+      file_text: "<?php\n\nfoo($alpha, $beta);\n".to_string(),
+      config: ConfigKeyMap::new(),
+      // the override configs are sent under the base config's id
+      override_configs: vec![ConfigKeyMap::new(), override_config, ConfigKeyMap::new()],
+      response: tx,
+    })
+    .unwrap();
+
+  let results = rx.recv().unwrap().unwrap();
+  assert_eq!(
+    results,
+    vec![
+      None,
+      Some("<?php\n\nfoo(\n    $alpha,\n    $beta,\n);\n".to_string()),
+      None,
+    ]
+  );
+}
@@ -57,3 +57,35 @@ fn should_return_none_for_non_php_files() {
   let result = format_text(&PathBuf::from("./file.js"), "const x = 1;", &config).unwrap();
   assert!(result.is_none());
 }
+
+#[test]
+fn should_report_unknown_ordered_class_element_groups() {
+  let mut config = ConfigKeyMap::new();
+  config.insert(
+    "orderedClassElements".to_string(),
+    ConfigKeyValue::Array(vec![
+      ConfigKeyValue::from_str("method-public"),
+      ConfigKeyValue::from_str("methods"),
+    ]),
+  );
+  let result = resolve_config(config, &GlobalConfiguration::default());
+  assert_eq!(result.diagnostics.len(), 1);
+  assert_eq!(result.diagnostics[0].property_name, "orderedClassElements");
+  assert_eq!(result.config.ordered_class_elements.unwrap().len(), 1);
+}
+
+#[test]
+fn should_report_sort_class_methods_with_ordered_class_elements() {
+  let mut config = ConfigKeyMap::new();
+  config.insert("sortClassMethods".to_string(), ConfigKeyValue::from_bool(true));
+  config.insert(
+    "orderedClassElements".to_string(),
+    ConfigKeyValue::Array(vec![
+      ConfigKeyValue::from_str("method-private"),
+      ConfigKeyValue::from_str("method-public"),
+    ]),
+  );
+  let result = resolve_config(config, &GlobalConfiguration::default());
+  assert_eq!(result.diagnostics.len(), 1);
+  assert_eq!(result.diagnostics[0].property_name, "sortClassMethods");
+}